@@ -0,0 +1,139 @@
+//! How the cost of sending grows with the number of observers, and what managing observers costs.
+//!
+//! Run with `cargo bench --bench fanout`.
+//
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures::{
+    executor::block_on,
+    sink::SinkExt,
+    stream::Stream,
+    task::{noop_waker_ref, Context, Poll},
+};
+use pharos::*;
+use std::pin::Pin;
+
+const OBSERVERS: [usize; 3] = [10, 100, 1000];
+
+// Room in a bounded channel. Consumers drain before it fills up, so sending never waits.
+//
+const BOUNDED: usize = 64;
+
+// A consumer that reads everything in its channel once every `rate` events, like a task that only
+// gets scheduled now and then.
+//
+struct Consumer {
+    events: Events<u64>,
+    rate: u64,
+}
+
+impl Consumer {
+    fn maybe_drain(&mut self, sent: u64) {
+        if !sent.is_multiple_of(self.rate) {
+            return;
+        }
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        while let Poll::Ready(Some(_)) = Pin::new(&mut self.events).poll_next(cx) {}
+    }
+}
+
+// A pharos with `n` consumers on `channel`, draining at rates spread between 1 and BOUNDED / 2.
+//
+fn setup(n: usize, channel: Channel) -> (Pharos<u64>, Vec<Consumer>) {
+    let mut pharos = Pharos::new(n);
+
+    let consumers = (0..n)
+        .map(|i| Consumer {
+            events: pharos.observe(channel.into()).expect("observe"),
+            rate: 1 + (i as u64 % (BOUNDED as u64 / 2)),
+        })
+        .collect();
+
+    (pharos, consumers)
+}
+
+fn fanout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fanout");
+
+    let channels = [
+        ("bounded", Channel::Bounded(BOUNDED)),
+        ("unbounded", Channel::Unbounded),
+    ];
+
+    for (name, channel) in channels {
+        for n in OBSERVERS {
+            group.throughput(Throughput::Elements(n as u64));
+
+            group.bench_with_input(BenchmarkId::new(name, n), &n, |b, &n| {
+                let (mut pharos, mut consumers) = setup(n, channel);
+                let mut sent = 0;
+
+                b.iter(|| {
+                    block_on(pharos.send(sent)).expect("send");
+                    sent += 1;
+
+                    consumers.iter_mut().for_each(|c| c.maybe_drain(sent));
+                });
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn broadcast(c: &mut Criterion) {
+    let mut group = c.benchmark_group("broadcast");
+
+    for n in OBSERVERS {
+        group.throughput(Throughput::Elements(n as u64));
+
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            let (mut pharos, mut consumers) = setup(n, Channel::Bounded(BOUNDED));
+            let mut sent = 0;
+
+            b.iter(|| {
+                pharos.broadcast(sent).expect("broadcast");
+                sent += 1;
+
+                consumers.iter_mut().for_each(|c| c.maybe_drain(sent));
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn observe_remove(c: &mut Criterion) {
+    let mut group = c.benchmark_group("observe_remove");
+
+    for n in OBSERVERS {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            let (mut pharos, _consumers) = setup(n, Channel::Unbounded);
+
+            b.iter(|| {
+                let events = pharos.observe(ObserveConfig::default()).expect("observe");
+                pharos.remove_observer(events.id()).expect("remove");
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn num_observers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("num_observers");
+
+    for n in OBSERVERS {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            let (mut pharos, _consumers) = setup(n, Channel::Unbounded);
+
+            b.iter(|| pharos.num_observers());
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, fanout, broadcast, observe_remove, num_observers);
+criterion_main!(benches);
@@ -0,0 +1,100 @@
+use crate::{import::*, Events};
+
+/// A type erased subscription, returned by [Events::boxed]. Unlike the result of
+/// [StreamExt::boxed](futures::StreamExt::boxed), it can still be closed, so subscriptions that went through
+/// different adapters can be stored together.
+//
+pub struct BoxedEvents<Event> {
+    inner: Box<dyn Subscription<Event> + Send>,
+}
+
+// The streams pharos hands out, which can all be closed.
+//
+pub(crate) trait Subscription<Event>: Stream<Item = Event> + Unpin {
+    fn close(&mut self);
+    fn is_closed(&self) -> bool;
+}
+
+impl<Event> BoxedEvents<Event> {
+    pub(crate) fn new(inner: impl Subscription<Event> + Send + 'static) -> Self {
+        Self {
+            inner: Box::new(inner),
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.inner.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+}
+
+impl<Event> Stream for BoxedEvents<Event> {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut *self.inner).poll_next(cx)
+    }
+}
+
+impl<Event> fmt::Debug for BoxedEvents<Event> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::BoxedEvents<{}>", type_name::<Event>())
+    }
+}
+
+impl<Event> Subscription<Event> for Events<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn close(&mut self) {
+        Events::close(self)
+    }
+
+    fn is_closed(&self) -> bool {
+        Events::is_closed(self)
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Subscriptions of different types can be stored together and still be closed.
+    //
+    #[test]
+    //
+    fn boxed() {
+        let mut ph = Pharos::default();
+
+        let mut subs = vec![
+            ph.observe(ObserveConfig::default())
+                .expect("observe")
+                .boxed(),
+            ph.observe(ObserveConfig::default())
+                .expect("observe")
+                .inspect(|_| {})
+                .boxed(),
+        ];
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+
+            for sub in &mut subs {
+                assert_eq!(Some(1), sub.next().await);
+
+                sub.close();
+                assert!(sub.is_closed());
+            }
+
+            assert_eq!(0, ph.num_observers());
+        });
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{import::*, Events};
+
+/// Stream returned by [Events::combine_latest]. Yields the most recent event of both subscriptions
+/// every time either of them has a new one, once both have sent at least one. Ends as soon as either
+/// subscription ends, closing the other one.
+//
+pub struct CombineLatest<Event, Other>
+where
+    Event: Clone + 'static + Sync + Send,
+    Other: Clone + 'static + Sync + Send,
+{
+    first: Events<Event>,
+    second: Events<Other>,
+    latest_first: Option<Event>,
+    latest_second: Option<Other>,
+
+    // Which stream to poll first next time, so a busy stream can't starve the other.
+    //
+    second_first: bool,
+    terminated: bool,
+}
+
+impl<Event, Other> CombineLatest<Event, Other>
+where
+    Event: Clone + 'static + Sync + Send,
+    Other: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(first: Events<Event>, second: Events<Other>) -> Self {
+        Self {
+            first,
+            second,
+            latest_first: None,
+            latest_second: None,
+            second_first: false,
+            terminated: false,
+        }
+    }
+
+    /// Close both subscriptions. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.first.close();
+        self.second.close();
+    }
+
+    /// Whether either subscription is closed, which means this stream will end. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.first.is_closed() || self.second.is_closed()
+    }
+
+    // Both latest values, if we have them.
+    //
+    fn latest(&self) -> Option<(Event, Other)> {
+        match (&self.latest_first, &self.latest_second) {
+            (Some(a), Some(b)) => Some((a.clone(), b.clone())),
+            _ => None,
+        }
+    }
+}
+
+// We never pin project, the latest events are only accessed through normal references.
+//
+impl<Event, Other> Unpin for CombineLatest<Event, Other>
+where
+    Event: Clone + 'static + Sync + Send,
+    Other: Clone + 'static + Sync + Send,
+{
+}
+
+impl<Event, Other> Stream for CombineLatest<Event, Other>
+where
+    Event: Clone + 'static + Sync + Send,
+    Other: Clone + 'static + Sync + Send,
+{
+    type Item = (Event, Other);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.terminated {
+            return Poll::Ready(None);
+        }
+
+        this.second_first = !this.second_first;
+
+        // Keep going while there are events, the first ones might arrive before the other side has any.
+        //
+        loop {
+            let mut progress = false;
+
+            for second in [this.second_first, !this.second_first] {
+                let next = if second {
+                    Pin::new(&mut this.second)
+                        .poll_next(cx)
+                        .map(|evt| evt.map(|evt| this.latest_second = Some(evt)))
+                } else {
+                    Pin::new(&mut this.first)
+                        .poll_next(cx)
+                        .map(|evt| evt.map(|evt| this.latest_first = Some(evt)))
+                };
+
+                match next {
+                    Poll::Pending => {}
+
+                    Poll::Ready(Some(())) => {
+                        progress = true;
+
+                        if let Some(both) = this.latest() {
+                            return Poll::Ready(Some(both));
+                        }
+                    }
+
+                    Poll::Ready(None) => {
+                        this.terminated = true;
+                        this.close();
+
+                        return Poll::Ready(None);
+                    }
+                }
+            }
+
+            if !progress {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+impl<Event, Other> FusedStream for CombineLatest<Event, Other>
+where
+    Event: Clone + 'static + Sync + Send,
+    Other: Clone + 'static + Sync + Send,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<Event, Other> fmt::Debug for CombineLatest<Event, Other>
+where
+    Event: Clone + 'static + Sync + Send,
+    Other: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pharos::CombineLatest<{}, {}>",
+            type_name::<Event>(),
+            type_name::<Other>()
+        )
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Every change on either side gives a new pair, once both have a value.
+    //
+    #[test]
+    //
+    fn combine_latest() {
+        let mut numbers = Pharos::<usize>::default();
+        let mut names = Pharos::<&'static str>::default();
+
+        let mut both = numbers
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .combine_latest(names.observe(ObserveConfig::default()).expect("observe"));
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        block_on(async {
+            numbers.send(1).await.expect("send");
+            numbers.send(2).await.expect("send");
+
+            assert_matches!(Pin::new(&mut both).poll_next(cx), Poll::Pending);
+
+            names.send("a").await.expect("send");
+            assert_eq!(Some((2, "a")), both.next().await);
+
+            names.send("b").await.expect("send");
+            assert_eq!(Some((2, "b")), both.next().await);
+
+            numbers.send(3).await.expect("send");
+            assert_eq!(Some((3, "b")), both.next().await);
+
+            // When one side ends, so does the combined stream.
+            //
+            drop(numbers);
+
+            assert_eq!(None, both.next().await);
+            assert!(both.is_terminated());
+            assert!(both.is_closed());
+            assert_eq!(0, names.num_observers());
+        });
+    }
+}
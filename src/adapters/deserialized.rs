@@ -0,0 +1,118 @@
+use crate::{import::*, Events};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+/// Stream returned by [Observable::observe_deserialized](crate::Observable::observe_deserialized).
+/// Every event is parsed as JSON into `T`. A malformed message comes out as an `Err` item and the
+/// stream goes on with the next one, so a single bad producer can't take down the subscription.
+//
+pub struct Deserialized<Event, T>
+where
+    Event: AsRef<[u8]> + Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+    _target: PhantomData<fn() -> T>,
+}
+
+impl<Event, T> Deserialized<Event, T>
+where
+    Event: AsRef<[u8]> + Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>) -> Self {
+        Self {
+            events,
+            _target: PhantomData,
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+}
+
+// We never pin project, T is only a marker.
+//
+impl<Event, T> Unpin for Deserialized<Event, T> where
+    Event: AsRef<[u8]> + Clone + 'static + Sync + Send
+{
+}
+
+impl<Event, T> Stream for Deserialized<Event, T>
+where
+    Event: AsRef<[u8]> + Clone + 'static + Sync + Send,
+    T: DeserializeOwned,
+{
+    type Item = Result<T, serde_json::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        let evt = ready!(Pin::new(&mut this.events).poll_next(cx));
+
+        Poll::Ready(evt.map(|bytes| serde_json::from_slice(bytes.as_ref())))
+    }
+}
+
+impl<Event, T> FusedStream for Deserialized<Event, T>
+where
+    Event: AsRef<[u8]> + Clone + 'static + Sync + Send,
+    T: DeserializeOwned,
+{
+    fn is_terminated(&self) -> bool {
+        self.events.is_terminated()
+    }
+}
+
+impl<Event, T> fmt::Debug for Deserialized<Event, T>
+where
+    Event: AsRef<[u8]> + Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pharos::Deserialized<{}, {}>",
+            type_name::<Event>(),
+            type_name::<T>()
+        )
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Valid messages are parsed, a malformed one comes out as an error without ending the stream.
+    //
+    #[test]
+    //
+    fn malformed() {
+        let mut ph: Pharos<Vec<u8>> = Pharos::default();
+
+        let events = ph
+            .observe_deserialized::<(String, u32)>(ObserveConfig::default())
+            .expect("observe");
+
+        block_on(async {
+            ph.send(br#"["a", 1]"#.to_vec()).await.expect("send");
+            ph.send(b"{ not json".to_vec()).await.expect("send");
+            ph.send(br#"["b", 2]"#.to_vec()).await.expect("send");
+            ph.close().await.expect("close");
+
+            let out = events.collect::<Vec<_>>().await;
+
+            assert_eq!(3, out.len());
+            assert_eq!(("a".to_string(), 1), *out[0].as_ref().expect("parse"));
+            assert!(out[1].is_err());
+            assert_eq!(("b".to_string(), 2), *out[2].as_ref().expect("parse"));
+        });
+    }
+}
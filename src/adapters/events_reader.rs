@@ -0,0 +1,147 @@
+use crate::{import::*, Events};
+
+/// [AsyncRead](futures_io::AsyncRead) returned by [Events::into_async_read]. Concatenates the bytes of
+/// all events.
+//
+pub struct EventsReader<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+
+    // The event we are reading from and how many of its bytes have been read already.
+    //
+    current: Option<Event>,
+    pos: usize,
+}
+
+impl<Event> EventsReader<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>) -> Self {
+        Self {
+            events,
+            current: None,
+            pos: 0,
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+}
+
+// We never pin project, the current event is only read through a normal reference.
+//
+impl<Event> Unpin for EventsReader<Event> where Event: Clone + 'static + Sync + Send {}
+
+impl<Event> AsyncRead for EventsReader<Event>
+where
+    Event: Clone + 'static + Sync + Send + AsRef<[u8]>,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        loop {
+            if let Some(evt) = &this.current {
+                let rest = &evt.as_ref()[this.pos..];
+
+                // Skip empty events, a read of 0 bytes would signal the end of the stream.
+                //
+                if !rest.is_empty() {
+                    let n = rest.len().min(buf.len());
+
+                    buf[..n].copy_from_slice(&rest[..n]);
+                    this.pos += n;
+
+                    return Poll::Ready(Ok(n));
+                }
+            }
+
+            match ready!(Pin::new(&mut this.events).poll_next(cx)) {
+                Some(evt) => {
+                    this.current = Some(evt);
+                    this.pos = 0;
+                }
+
+                None => {
+                    this.current = None;
+                    return Poll::Ready(Ok(0));
+                }
+            }
+        }
+    }
+}
+
+impl<Event> fmt::Debug for EventsReader<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::EventsReader<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+    use futures::io::AsyncReadExt;
+
+    // Chunks are concatenated, also when the buffer is smaller than a chunk.
+    //
+    #[test]
+    //
+    fn read() {
+        let mut ph = Pharos::default();
+
+        let events = ph.observe(ObserveConfig::default()).expect("observe");
+        let small = ph.observe(ObserveConfig::default()).expect("observe");
+
+        let mut reader = events.into_async_read();
+        let mut small = small.into_async_read();
+
+        block_on(async {
+            for chunk in [&b"hel"[..], b"", b"lo"] {
+                ph.send(chunk.to_vec()).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            let mut out = String::new();
+            reader.read_to_string(&mut out).await.expect("read");
+
+            assert_eq!("hello", out);
+
+            let mut buf = [0; 2];
+
+            assert_eq!(2, small.read(&mut buf).await.expect("read"));
+            assert_eq!(b"he", &buf);
+
+            assert_eq!(1, small.read(&mut buf).await.expect("read"));
+            assert_eq!(b"l", &buf[..1]);
+
+            assert_eq!(2, small.read(&mut buf).await.expect("read"));
+            assert_eq!(b"lo", &buf);
+
+            assert_eq!(0, small.read(&mut buf).await.expect("read"));
+        });
+    }
+}
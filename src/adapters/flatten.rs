@@ -0,0 +1,136 @@
+use crate::{import::*, Events};
+
+/// Stream returned by [Events::flatten]. For events that are streams themselves, yields the items of
+/// each of them in turn: the next event is only taken once the current one has ended.
+///
+/// Closing stops new streams from arriving. The stream that is being drained and the ones that were
+/// already queued are still drained.
+//
+pub struct Flatten<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+
+    // The event whose items we are yielding.
+    //
+    current: Option<Event>,
+}
+
+impl<Event> Flatten<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>) -> Self {
+        Self {
+            events,
+            current: None,
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+}
+
+// We never pin project, the current stream is only polled when it's Unpin.
+//
+impl<Event> Unpin for Flatten<Event> where Event: Clone + 'static + Sync + Send {}
+
+impl<Event> Stream for Flatten<Event>
+where
+    Event: Stream + Unpin + Clone + 'static + Sync + Send,
+{
+    type Item = Event::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(current) = &mut this.current {
+                match ready!(Pin::new(current).poll_next(cx)) {
+                    Some(item) => return Poll::Ready(Some(item)),
+                    None => this.current = None,
+                }
+            }
+
+            match ready!(Pin::new(&mut this.events).poll_next(cx)) {
+                Some(next) => this.current = Some(next),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl<Event> fmt::Debug for Flatten<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::Flatten<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+    use futures::stream::{iter, Iter};
+
+    type Inner = Iter<std::vec::IntoIter<usize>>;
+
+    // The items of every stream come out in order, and the flattened stream ends with the outer one.
+    //
+    #[test]
+    //
+    fn in_order() {
+        let mut ph = Pharos::<Inner>::default();
+        let events = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            ph.send(iter(vec![1, 2])).await.expect("send");
+            ph.send(iter(vec![])).await.expect("send");
+            ph.send(iter(vec![3, 4, 5])).await.expect("send");
+            ph.close().await.expect("close");
+
+            assert_eq!(
+                vec![1, 2, 3, 4, 5],
+                events.flatten().collect::<Vec<_>>().await
+            );
+        });
+    }
+
+    // Closing lets the queued streams drain, but no new ones come in.
+    //
+    #[test]
+    //
+    fn close() {
+        let mut ph = Pharos::<Inner>::default();
+        let mut events = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .flatten();
+
+        block_on(async {
+            ph.send(iter(vec![1, 2])).await.expect("send");
+            ph.send(iter(vec![3])).await.expect("send");
+
+            assert_eq!(Some(1), events.next().await);
+
+            events.close();
+            assert!(events.is_closed());
+
+            assert!(ph.send(iter(vec![4])).await.is_ok());
+
+            assert_eq!(vec![2, 3], events.collect::<Vec<_>>().await);
+        });
+    }
+}
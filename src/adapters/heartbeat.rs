@@ -0,0 +1,160 @@
+use crate::{import::*, Events, Timer};
+
+/// An item of [Heartbeats]: either a real event or a heartbeat.
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+//
+pub enum Heartbeat<Event> {
+    /// An event from the observable.
+    //
+    Event(Event),
+
+    /// Nothing happened for an interval, but the subscription is alive.
+    //
+    Beat,
+}
+
+/// Stream returned by [Pharos::observe_heartbeat](crate::Pharos::observe_heartbeat). Yields the events
+/// of the subscription as [Heartbeat::Event] and a [Heartbeat::Beat] each time an interval goes by
+/// without any. The interval starts over after every item, so beats only come while it's quiet.
+//
+pub struct Heartbeats<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+    interval: Duration,
+    timer: Arc<dyn Timer>,
+
+    // Resolves when the next beat is due.
+    //
+    sleep: Pin<Box<dyn Future<Output = ()> + Send>>,
+}
+
+impl<Event> Heartbeats<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>, interval: Duration, timer: Arc<dyn Timer>) -> Self {
+        Self {
+            sleep: timer.sleep(interval),
+            events,
+            interval,
+            timer,
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+}
+
+// We never pin project, the sleep future is boxed.
+//
+impl<Event> Unpin for Heartbeats<Event> where Event: Clone + 'static + Sync + Send {}
+
+impl<Event> Stream for Heartbeats<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    type Item = Heartbeat<Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        // A subscription that ended is no longer alive, so it doesn't beat.
+        //
+        if let Poll::Ready(evt) = Pin::new(&mut this.events).poll_next(cx) {
+            this.sleep = this.timer.sleep(this.interval);
+
+            return Poll::Ready(evt.map(Heartbeat::Event));
+        }
+
+        ready!(this.sleep.as_mut().poll(cx));
+
+        this.sleep = this.timer.sleep(this.interval);
+
+        Poll::Ready(Some(Heartbeat::Beat))
+    }
+}
+
+impl<Event> FusedStream for Heartbeats<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn is_terminated(&self) -> bool {
+        self.events.is_terminated()
+    }
+}
+
+impl<Event> fmt::Debug for Heartbeats<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::Heartbeats<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Beats come once per quiet interval, real events reset the interval.
+    //
+    #[test]
+    //
+    fn beats_while_quiet() {
+        let timer = timer::MockTimer::new();
+        let mut ph = Pharos::default();
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        ph.set_timer(Some(Arc::new(timer.clone())));
+
+        let mut events = ph
+            .observe_heartbeat(Duration::from_secs(1), ObserveConfig::default())
+            .expect("observe");
+
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        timer.advance(Duration::from_secs(1));
+        assert_eq!(
+            Poll::Ready(Some(Heartbeat::Beat)),
+            Pin::new(&mut events).poll_next(cx)
+        );
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        timer.advance(Duration::from_millis(500));
+
+        block_on(ph.send(1)).expect("send");
+
+        assert_eq!(
+            Poll::Ready(Some(Heartbeat::Event(1))),
+            Pin::new(&mut events).poll_next(cx)
+        );
+
+        // The interval started over with the event.
+        //
+        timer.advance(Duration::from_millis(500));
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        timer.advance(Duration::from_millis(500));
+        assert_eq!(
+            Poll::Ready(Some(Heartbeat::Beat)),
+            Pin::new(&mut events).poll_next(cx)
+        );
+
+        block_on(ph.close()).expect("close");
+
+        assert_eq!(Poll::Ready(None), Pin::new(&mut events).poll_next(cx));
+    }
+}
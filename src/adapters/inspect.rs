@@ -0,0 +1,125 @@
+use crate::{adapters::Subscription, import::*, BoxedEvents, Events};
+
+/// Stream returned by [Events::inspect]. Calls a closure with a reference to every event before
+/// passing it on unchanged.
+//
+pub struct Inspect<Event, F>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+    f: F,
+}
+
+impl<Event, F> Inspect<Event, F>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>, f: F) -> Self {
+        Self { events, f }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+
+    /// Erase the type of this stream. See [Events::boxed].
+    //
+    pub fn boxed(self) -> BoxedEvents<Event>
+    where
+        F: FnMut(&Event) + Send + 'static,
+    {
+        BoxedEvents::new(self)
+    }
+}
+
+impl<Event, F> Subscription<Event> for Inspect<Event, F>
+where
+    Event: Clone + 'static + Sync + Send,
+    F: FnMut(&Event),
+{
+    fn close(&mut self) {
+        Inspect::close(self)
+    }
+
+    fn is_closed(&self) -> bool {
+        Inspect::is_closed(self)
+    }
+}
+
+// We never pin project, the closure is just called through a mutable reference.
+//
+impl<Event, F> Unpin for Inspect<Event, F> where Event: Clone + 'static + Sync + Send {}
+
+impl<Event, F> Stream for Inspect<Event, F>
+where
+    Event: Clone + 'static + Sync + Send,
+    F: FnMut(&Event),
+{
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let evt = ready!(Pin::new(&mut this.events).poll_next(cx));
+
+        if let Some(evt) = &evt {
+            (this.f)(evt);
+        }
+
+        Poll::Ready(evt)
+    }
+}
+
+impl<Event, F> fmt::Debug for Inspect<Event, F>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::Inspect<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // The closure sees every event and the events come through unchanged.
+    //
+    #[test]
+    //
+    fn count() {
+        let mut ph = Pharos::default();
+        let mut count = 0;
+
+        let mut events = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .inspect(|_| count += 1);
+
+        block_on(async {
+            for i in 1..=3 {
+                ph.send(i).await.expect("send");
+            }
+
+            assert_eq!(Some(1), events.next().await);
+
+            events.close();
+            assert!(events.is_closed());
+
+            assert_eq!(vec![2, 3], (&mut events).collect::<Vec<usize>>().await);
+        });
+
+        drop(events);
+
+        assert_eq!(count, 3);
+    }
+}
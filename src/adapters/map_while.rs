@@ -0,0 +1,144 @@
+use crate::{import::*, Events};
+
+/// Stream returned by [Events::map_while]. Transforms events with a closure until it returns `None`,
+/// which ends the stream and closes the subscription.
+//
+pub struct MapWhile<Event, F>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+    f: F,
+
+    // Set once the closure returned `None`.
+    //
+    done: bool,
+}
+
+impl<Event, F> MapWhile<Event, F>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>, f: F) -> Self {
+        Self {
+            events,
+            f,
+            done: false,
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+}
+
+// We never pin project, the closure is only called through a normal reference.
+//
+impl<Event, F> Unpin for MapWhile<Event, F> where Event: Clone + 'static + Sync + Send {}
+
+impl<Event, F, U> Stream for MapWhile<Event, F>
+where
+    Event: Clone + 'static + Sync + Send,
+    F: FnMut(Event) -> Option<U>,
+{
+    type Item = U;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        // Once the closure has ended the stream, we don't call it again. Remaining events
+        // are discarded. After a call to close, the queued events still come through.
+        //
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        let evt = match ready!(Pin::new(&mut this.events).poll_next(cx)) {
+            Some(evt) => evt,
+            None => return Poll::Ready(None),
+        };
+
+        let out = (this.f)(evt);
+
+        if out.is_none() {
+            this.done = true;
+            this.events.close();
+        }
+
+        Poll::Ready(out)
+    }
+}
+
+impl<Event, F> fmt::Debug for MapWhile<Event, F>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::MapWhile<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // The sentinel ends the stream and closes the subscription.
+    //
+    #[test]
+    //
+    fn until_sentinel() {
+        let mut ph = Pharos::default();
+
+        let mut evts = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .map_while(|n: usize| if n != 0 { Some(n * 10) } else { None });
+
+        block_on(async {
+            for n in [1, 2, 0, 3] {
+                ph.send(n).await.expect("send");
+            }
+
+            assert_eq!(Some(10), evts.next().await);
+            assert_eq!(Some(20), evts.next().await);
+            assert_eq!(None, evts.next().await);
+            assert!(evts.is_closed());
+            assert_eq!(None, evts.next().await);
+
+            assert_eq!(0, ph.num_observers());
+        });
+    }
+
+    // Closing the subscription keeps the events that were already queued.
+    //
+    #[test]
+    //
+    fn close_keeps_queued() {
+        let mut ph = Pharos::default();
+
+        let mut evts = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .map_while(|n: usize| if n != 0 { Some(n * 10) } else { None });
+
+        block_on(async {
+            for n in [1, 2, 3] {
+                ph.send(n).await.expect("send");
+            }
+
+            evts.close();
+
+            assert!(evts.is_closed());
+            assert_eq!(vec![10, 20, 30], evts.collect::<Vec<_>>().await);
+        });
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{adapters::Subscription, import::*, BoxedEvents, Events};
+
+/// Stream returned by [Events::merge]. Interleaves the events of two subscriptions and ends once
+/// both have ended.
+//
+#[derive(Debug)]
+//
+pub struct MergedEvents<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    first: Events<Event>,
+    second: Events<Event>,
+
+    // Which stream to poll first next time, so a busy stream can't starve the other.
+    //
+    second_first: bool,
+}
+
+impl<Event> MergedEvents<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(first: Events<Event>, second: Events<Event>) -> Self {
+        Self {
+            first,
+            second,
+            second_first: false,
+        }
+    }
+
+    /// Close both subscriptions. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.first.close();
+        self.second.close();
+    }
+
+    /// Whether both subscriptions are closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.first.is_closed() && self.second.is_closed()
+    }
+
+    /// Erase the type of this stream. See [Events::boxed].
+    //
+    pub fn boxed(self) -> BoxedEvents<Event> {
+        BoxedEvents::new(self)
+    }
+}
+
+impl<Event> Subscription<Event> for MergedEvents<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn close(&mut self) {
+        MergedEvents::close(self)
+    }
+
+    fn is_closed(&self) -> bool {
+        MergedEvents::is_closed(self)
+    }
+}
+
+impl<Event> Stream for MergedEvents<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        this.second_first = !this.second_first;
+
+        let (a, b) = if this.second_first {
+            (&mut this.second, &mut this.first)
+        } else {
+            (&mut this.first, &mut this.second)
+        };
+
+        for events in [a, b] {
+            if events.is_terminated() {
+                continue;
+            }
+
+            if let Poll::Ready(Some(evt)) = Pin::new(events).poll_next(cx) {
+                return Poll::Ready(Some(evt));
+            }
+        }
+
+        if this.first.is_terminated() && this.second.is_terminated() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<Event> FusedStream for MergedEvents<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn is_terminated(&self) -> bool {
+        self.first.is_terminated() && self.second.is_terminated()
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Events from both subscriptions arrive and the stream only ends when both have.
+    //
+    #[test]
+    //
+    fn merge() {
+        let mut ph1 = Pharos::default();
+        let mut ph2 = Pharos::default();
+
+        let a = ph1.observe(ObserveConfig::default()).expect("observe");
+        let b = ph2.observe(ObserveConfig::default()).expect("observe");
+
+        let mut merged = a.merge(b);
+
+        block_on(async {
+            ph1.send(1).await.expect("send");
+            ph2.send(2).await.expect("send");
+            ph1.close().await.expect("close");
+
+            let mut got = vec![merged.next().await, merged.next().await];
+            got.sort();
+
+            assert_eq!(vec![Some(1), Some(2)], got);
+
+            ph2.send(3).await.expect("send");
+
+            assert_eq!(Some(3), merged.next().await);
+            assert!(!merged.is_terminated());
+
+            ph2.close().await.expect("close");
+
+            assert_eq!(None, merged.next().await);
+            assert!(merged.is_terminated());
+            assert!(merged.is_closed());
+        });
+    }
+
+    // Closing the merged stream closes both subscriptions.
+    //
+    #[test]
+    //
+    fn close() {
+        let mut ph = Pharos::<usize>::default();
+
+        let a = ph.observe(ObserveConfig::default()).expect("observe");
+        let b = ph.observe(ObserveConfig::default()).expect("observe");
+
+        let mut merged = a.merge(b);
+
+        merged.close();
+
+        assert!(merged.is_closed());
+        assert_eq!(0, ph.num_observers());
+    }
+}
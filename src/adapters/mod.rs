@@ -0,0 +1,42 @@
+//! Stream adapters for [Events](crate::Events) which, unlike the generic ones from `futures`,
+//! keep giving access to the subscription so it can still be closed.
+
+mod boxed;
+mod combine_latest;
+#[cfg(feature = "serde")]
+mod deserialized;
+mod events_reader;
+mod flatten;
+mod heartbeat;
+mod inspect;
+mod map_while;
+mod merge;
+mod ready_chunks;
+mod result_events;
+mod sampled;
+mod scan;
+mod take_until;
+mod throttle;
+
+pub(crate) use boxed::Subscription;
+
+#[cfg(feature = "serde")]
+//
+pub use deserialized::Deserialized;
+
+pub use {
+    boxed::BoxedEvents,
+    combine_latest::CombineLatest,
+    events_reader::EventsReader,
+    flatten::Flatten,
+    heartbeat::{Heartbeat, Heartbeats},
+    inspect::Inspect,
+    map_while::MapWhile,
+    merge::MergedEvents,
+    ready_chunks::ReadyChunks,
+    result_events::ResultEvents,
+    sampled::Sampled,
+    scan::Scan,
+    take_until::TakeUntil,
+    throttle::{Throttle, ThrottlePolicy},
+};
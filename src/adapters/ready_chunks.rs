@@ -0,0 +1,111 @@
+use crate::{import::*, Events};
+
+/// Stream returned by [Events::ready_chunks]. Yields a `Vec` of all events that were
+/// immediately available, with at most `cap` events per batch.
+//
+#[derive(Debug)]
+//
+pub struct ReadyChunks<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+    cap: usize,
+}
+
+impl<Event> ReadyChunks<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>, cap: usize) -> Self {
+        assert!(cap > 0, "ReadyChunks: cap must be at least 1");
+
+        Self { events, cap }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+}
+
+impl<Event> Stream for ReadyChunks<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    type Item = Vec<Event>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let first = match ready!(Pin::new(&mut self.events).poll_next(cx)) {
+            Some(evt) => evt,
+            None => return Poll::Ready(None),
+        };
+
+        let mut batch = Vec::with_capacity(self.cap);
+        batch.push(first);
+
+        while batch.len() < self.cap {
+            match Pin::new(&mut self.events).poll_next(cx) {
+                Poll::Ready(Some(evt)) => batch.push(evt),
+
+                // If the stream ended, we will return None on the next poll.
+                //
+                Poll::Ready(None) | Poll::Pending => break,
+            }
+        }
+
+        Poll::Ready(Some(batch))
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Batches never exceed cap and don't wait to be filled up.
+    //
+    #[test]
+    //
+    fn batches() {
+        let mut ph = Pharos::default();
+        let mut chunks = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .ready_chunks(3);
+
+        block_on(poll_fn(move |cx| {
+            assert_eq!(Pin::new(&mut chunks).poll_next(cx), Poll::Pending);
+
+            for i in 1..=5 {
+                assert_matches!(Pin::new(&mut ph).poll_ready(cx), Poll::Ready(Ok(_)));
+                Pin::new(&mut ph).start_send(i).expect("start_send");
+            }
+
+            assert_eq!(
+                Pin::new(&mut chunks).poll_next(cx),
+                Poll::Ready(Some(vec![1, 2, 3]))
+            );
+            assert_eq!(
+                Pin::new(&mut chunks).poll_next(cx),
+                Poll::Ready(Some(vec![4, 5]))
+            );
+            assert_eq!(Pin::new(&mut chunks).poll_next(cx), Poll::Pending);
+
+            assert!(!chunks.is_closed());
+            chunks.close();
+            assert!(chunks.is_closed());
+
+            assert_eq!(Pin::new(&mut chunks).poll_next(cx), Poll::Ready(None));
+
+            ().into()
+        }));
+    }
+}
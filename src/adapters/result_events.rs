@@ -0,0 +1,129 @@
+use crate::{import::*, Error, ErrorKind, Events};
+
+/// Stream returned by [Pharos::observe_result](crate::Pharos::observe_result). Yields the events as
+/// `Ok`, and tells why the subscription ended: when the observable closes or goes away, the last item
+/// is an error of kind [ErrorKind::Closed]. When the consumer closes the stream with
+/// [ResultEvents::close], it just ends with `None` once the queued events are read.
+//
+pub struct ResultEvents<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+    done: bool,
+}
+
+impl<Event> ResultEvents<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>) -> Self {
+        Self {
+            events,
+            done: false,
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+}
+
+// We never pin project.
+//
+impl<Event> Unpin for ResultEvents<Event> where Event: Clone + 'static + Sync + Send {}
+
+impl<Event> Stream for ResultEvents<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    type Item = Result<Event, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        // Until the stream ends, it's only closed if the consumer closed it.
+        //
+        let closed_by_us = this.events.is_closed();
+
+        match ready!(Pin::new(&mut this.events).poll_next(cx)) {
+            Some(evt) => Poll::Ready(Some(Ok(evt))),
+
+            None => {
+                this.done = true;
+
+                if closed_by_us {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Ready(Some(Err(ErrorKind::Closed.into())))
+                }
+            }
+        }
+    }
+}
+
+impl<Event> fmt::Debug for ResultEvents<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::ResultEvents<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Closing the pharos ends the stream with an error.
+    //
+    #[test]
+    //
+    fn producer_closed() {
+        let mut ph = Pharos::default();
+        let mut evts = ph
+            .observe_result(ObserveConfig::default())
+            .expect("observe");
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            ph.close().await.expect("close");
+
+            assert_matches!(evts.next().await, Some(Ok(1)));
+            assert_matches!(evts.next().await, Some(Err(e)) if e.kind() == ErrorKind::Closed);
+            assert_matches!(evts.next().await, None);
+        });
+    }
+
+    // Closing the stream ourselves ends it without an error.
+    //
+    #[test]
+    //
+    fn consumer_closed() {
+        let mut ph = Pharos::default();
+        let mut evts = ph
+            .observe_result(ObserveConfig::default())
+            .expect("observe");
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            evts.close();
+
+            assert_matches!(evts.next().await, Some(Ok(1)));
+            assert_matches!(evts.next().await, None);
+        });
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{import::*, Events, Timer};
+
+/// Stream returned by [Pharos::observe_sampled_time](crate::Pharos::observe_sampled_time). Yields at most
+/// one event per window: the most recent event that arrived during it, once the window is over. Windows
+/// in which nothing arrived yield nothing. When the subscription ends, the last event that is still held
+/// back comes out right away.
+//
+pub struct Sampled<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+    window: Duration,
+    timer: Arc<dyn Timer>,
+
+    // Resolves at the end of the current window.
+    //
+    sleep: Pin<Box<dyn Future<Output = ()> + Send>>,
+
+    // The most recent event in the current window.
+    //
+    latest: Option<Event>,
+}
+
+impl<Event> Sampled<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>, window: Duration, timer: Arc<dyn Timer>) -> Self {
+        Self {
+            sleep: timer.sleep(window),
+            events,
+            window,
+            timer,
+            latest: None,
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+}
+
+// We never pin project, the latest event is only moved around by value.
+//
+impl<Event> Unpin for Sampled<Event> where Event: Clone + 'static + Sync + Send {}
+
+impl<Event> Stream for Sampled<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        // Take what arrived, so the channel doesn't fill up.
+        //
+        while !this.events.is_terminated() {
+            match Pin::new(&mut this.events).poll_next(cx) {
+                Poll::Ready(Some(evt)) => this.latest = Some(evt),
+                Poll::Ready(None) | Poll::Pending => break,
+            }
+        }
+
+        if this.events.is_terminated() {
+            return Poll::Ready(this.latest.take());
+        }
+
+        loop {
+            ready!(this.sleep.as_mut().poll(cx));
+
+            this.sleep = this.timer.sleep(this.window);
+
+            if let Some(evt) = this.latest.take() {
+                return Poll::Ready(Some(evt));
+            }
+        }
+    }
+}
+
+impl<Event> fmt::Debug for Sampled<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::Sampled<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Only the last event of a busy window comes out, at the end of the window.
+    //
+    #[test]
+    //
+    fn latest_per_window() {
+        let timer = timer::MockTimer::new();
+        let mut ph = Pharos::default();
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        ph.set_timer(Some(Arc::new(timer.clone())));
+
+        let mut events = ph
+            .observe_sampled_time(Duration::from_secs(1), ObserveConfig::default())
+            .expect("observe");
+
+        block_on(async {
+            for i in 1..=5 {
+                ph.send(i).await.expect("send");
+            }
+        });
+
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        timer.advance(Duration::from_secs(1));
+        assert_eq!(Poll::Ready(Some(5)), Pin::new(&mut events).poll_next(cx));
+
+        // Nothing arrived in this window.
+        //
+        timer.advance(Duration::from_secs(1));
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        block_on(async {
+            ph.send(6).await.expect("send");
+            ph.send(7).await.expect("send");
+        });
+
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        timer.advance(Duration::from_secs(1));
+        assert_eq!(Poll::Ready(Some(7)), Pin::new(&mut events).poll_next(cx));
+
+        block_on(async {
+            ph.send(8).await.expect("send");
+            ph.close().await.expect("close");
+        });
+
+        assert_eq!(Poll::Ready(Some(8)), Pin::new(&mut events).poll_next(cx));
+        assert_eq!(Poll::Ready(None), Pin::new(&mut events).poll_next(cx));
+    }
+}
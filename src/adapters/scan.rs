@@ -0,0 +1,184 @@
+use crate::{import::*, Events};
+
+/// Stream returned by [Events::scan]. Runs a closure over every event with access to some state,
+/// yielding whatever the closure returns. The stream ends when the closure returns `None`, which also
+/// closes the subscription.
+//
+pub struct Scan<Event, S, F>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+    state: S,
+    f: F,
+
+    // Set once the closure returned `None`.
+    //
+    done: bool,
+}
+
+impl<Event, S, F> Scan<Event, S, F>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>, state: S, f: F) -> Self {
+        Self {
+            events,
+            state,
+            f,
+            done: false,
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+
+    /// The current state.
+    //
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+// We never pin project, the closure and the state are just moved around as needed.
+//
+impl<Event, S, F> Unpin for Scan<Event, S, F> where Event: Clone + 'static + Sync + Send {}
+
+impl<Event, S, F, U> Stream for Scan<Event, S, F>
+where
+    Event: Clone + 'static + Sync + Send,
+    F: FnMut(&mut S, Event) -> Option<U>,
+{
+    type Item = U;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        // Once the closure has ended the stream, we don't call it again. Remaining events
+        // are discarded. After a call to close, the queued events still come through.
+        //
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        let evt = match ready!(Pin::new(&mut this.events).poll_next(cx)) {
+            Some(evt) => evt,
+            None => return Poll::Ready(None),
+        };
+
+        let out = (this.f)(&mut this.state, evt);
+
+        if out.is_none() {
+            this.done = true;
+            this.events.close();
+        }
+
+        Poll::Ready(out)
+    }
+}
+
+impl<Event, S, F> fmt::Debug for Scan<Event, S, F>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::Scan<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Running sum of the received integers.
+    //
+    #[test]
+    //
+    fn running_sum() {
+        let mut ph = Pharos::default();
+
+        let sums = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .scan(0, |sum, n| {
+                *sum += n;
+                Some(*sum)
+            });
+
+        block_on(async {
+            for n in 1..=4 {
+                ph.send(n).await.expect("send");
+            }
+
+            drop(ph);
+
+            assert_eq!(vec![1, 3, 6, 10], sums.collect::<Vec<usize>>().await);
+        });
+    }
+
+    // Returning None ends the stream and closes the subscription.
+    //
+    #[test]
+    //
+    fn ends_on_none() {
+        let mut ph = Pharos::default();
+
+        let mut evts = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .scan((), |_, n| if n < 3 { Some(n) } else { None });
+
+        block_on(async {
+            for n in 1..=4 {
+                ph.send(n).await.expect("send");
+            }
+
+            assert_eq!(Some(1), evts.next().await);
+            assert_eq!(Some(2), evts.next().await);
+            assert_eq!(None, evts.next().await);
+            assert!(evts.is_closed());
+            assert_eq!(None, evts.next().await);
+
+            // The pharos notices the observer is gone.
+            //
+            assert_eq!(0, ph.num_observers());
+        });
+    }
+
+    // Closing the subscription keeps the events that were already queued.
+    //
+    #[test]
+    //
+    fn close_keeps_queued() {
+        let mut ph = Pharos::default();
+
+        let mut sums = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .scan(0, |sum, n| {
+                *sum += n;
+                Some(*sum)
+            });
+
+        block_on(async {
+            for n in 1..=3 {
+                ph.send(n).await.expect("send");
+            }
+
+            sums.close();
+
+            assert!(sums.is_closed());
+            assert_eq!(vec![1, 3, 6], sums.collect::<Vec<usize>>().await);
+        });
+    }
+}
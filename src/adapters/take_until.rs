@@ -0,0 +1,117 @@
+use crate::{import::*, Events};
+
+/// Stream returned by [Events::take_until]. Yields events until a future resolves, which ends the
+/// stream and closes the subscription.
+//
+pub struct TakeUntil<Event, Fut>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+
+    // `None` once it has resolved.
+    //
+    until: Option<Pin<Box<Fut>>>,
+}
+
+impl<Event, Fut> TakeUntil<Event, Fut>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(events: Events<Event>, until: Fut) -> Self {
+        Self {
+            events,
+            until: Some(Box::pin(until)),
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+}
+
+// We never pin project, the future is pinned on the heap.
+//
+impl<Event, Fut> Unpin for TakeUntil<Event, Fut> where Event: Clone + 'static + Sync + Send {}
+
+impl<Event, Fut> Stream for TakeUntil<Event, Fut>
+where
+    Event: Clone + 'static + Sync + Send,
+    Fut: Future,
+{
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        let until = match &mut this.until {
+            Some(until) => until,
+
+            // Events that were still queued are discarded.
+            //
+            None => return Poll::Ready(None),
+        };
+
+        if until.as_mut().poll(cx).is_ready() {
+            this.until = None;
+            this.events.close();
+
+            return Poll::Ready(None);
+        }
+
+        Pin::new(&mut this.events).poll_next(cx)
+    }
+}
+
+impl<Event, Fut> fmt::Debug for TakeUntil<Event, Fut>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::TakeUntil<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Once the signal fires the stream ends, even with events still queued.
+    //
+    #[test]
+    //
+    fn signal() {
+        let mut ph = Pharos::default();
+        let (stop, signal) = oneshot::channel::<()>();
+
+        let mut evts = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .take_until(signal);
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            assert_eq!(Some(1), evts.next().await);
+
+            ph.send(2).await.expect("send");
+            stop.send(()).expect("signal");
+
+            assert_eq!(None, evts.next().await);
+            assert!(evts.is_closed());
+
+            ph.send(3).await.expect("send");
+
+            assert_eq!(None, evts.next().await);
+            assert_eq!(0, ph.num_observers());
+        });
+    }
+}
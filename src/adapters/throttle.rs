@@ -0,0 +1,203 @@
+use crate::{import::*, Events, Timer};
+
+/// What [Events::throttle] does with the events that arrive while it waits for the interval to pass.
+//
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+//
+pub enum ThrottlePolicy {
+    /// Drop them. The first event that arrives after the interval is yielded.
+    //
+    Drop,
+
+    /// Keep only the most recent one and yield it as soon as the interval has passed.
+    //
+    Latest,
+}
+
+/// Stream returned by [Events::throttle]. Yields at most one event per interval.
+//
+pub struct Throttle<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    events: Events<Event>,
+    interval: Duration,
+    policy: ThrottlePolicy,
+    timer: Arc<dyn Timer>,
+
+    // Set while we wait for the interval to pass after yielding an event.
+    //
+    sleep: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+
+    // The event to yield once the interval has passed, with ThrottlePolicy::Latest.
+    //
+    latest: Option<Event>,
+}
+
+impl<Event> Throttle<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(
+        events: Events<Event>,
+        interval: Duration,
+        policy: ThrottlePolicy,
+        timer: Arc<dyn Timer>,
+    ) -> Self {
+        Self {
+            events,
+            interval,
+            policy,
+            timer,
+            sleep: None,
+            latest: None,
+        }
+    }
+
+    /// Close the underlying subscription. See [Events::close].
+    //
+    pub fn close(&mut self) {
+        self.events.close();
+    }
+
+    /// Whether the underlying subscription is closed. See [Events::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.events.is_closed()
+    }
+
+    fn emit(&mut self, evt: Event) -> Poll<Option<Event>> {
+        self.sleep = Some(self.timer.sleep(self.interval));
+
+        Poll::Ready(Some(evt))
+    }
+}
+
+// We never pin project, the latest event is only moved around by value.
+//
+impl<Event> Unpin for Throttle<Event> where Event: Clone + 'static + Sync + Send {}
+
+impl<Event> Stream for Throttle<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Some(sleep) = &mut this.sleep {
+            // Take what arrives in the meantime, so the channel doesn't fill up.
+            //
+            while !this.events.is_terminated() {
+                match Pin::new(&mut this.events).poll_next(cx) {
+                    Poll::Ready(Some(evt)) => {
+                        if this.policy == ThrottlePolicy::Latest {
+                            this.latest = Some(evt);
+                        }
+                    }
+
+                    Poll::Ready(None) | Poll::Pending => break,
+                }
+            }
+
+            if this.events.is_terminated() && this.latest.is_none() {
+                return Poll::Ready(None);
+            }
+
+            ready!(sleep.as_mut().poll(cx));
+            this.sleep = None;
+
+            if let Some(evt) = this.latest.take() {
+                return this.emit(evt);
+            }
+        }
+
+        match ready!(Pin::new(&mut this.events).poll_next(cx)) {
+            Some(evt) => this.emit(evt),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl<Event> fmt::Debug for Throttle<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::Throttle<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    fn throttled(policy: ThrottlePolicy) -> (Pharos<usize>, Throttle<usize>, timer::MockTimer) {
+        let timer = timer::MockTimer::new();
+        let mut ph = Pharos::default();
+
+        let events = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .throttle(Duration::from_secs(1), policy, Arc::new(timer.clone()));
+
+        (ph, events, timer)
+    }
+
+    // The most recent event of a burst comes out once the interval has passed.
+    //
+    #[test]
+    //
+    fn keep_latest() {
+        let (mut ph, mut events, timer) = throttled(ThrottlePolicy::Latest);
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        block_on(async {
+            for i in 1..=3 {
+                ph.send(i).await.expect("send");
+            }
+        });
+
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut events).poll_next(cx));
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        timer.advance(Duration::from_millis(500));
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        timer.advance(Duration::from_millis(500));
+        assert_eq!(Poll::Ready(Some(3)), Pin::new(&mut events).poll_next(cx));
+
+        block_on(ph.close()).expect("close");
+
+        assert_eq!(Poll::Ready(None), Pin::new(&mut events).poll_next(cx));
+    }
+
+    // Events in the interval are lost, the next one after it comes through right away.
+    //
+    #[test]
+    //
+    fn drop_between() {
+        let (mut ph, mut events, timer) = throttled(ThrottlePolicy::Drop);
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        block_on(async {
+            for i in 1..=3 {
+                ph.send(i).await.expect("send");
+            }
+        });
+
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut events).poll_next(cx));
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        timer.advance(Duration::from_secs(1));
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        block_on(ph.send(4)).expect("send");
+        assert_eq!(Poll::Ready(Some(4)), Pin::new(&mut events).poll_next(cx));
+
+        events.close();
+        assert!(events.is_closed());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::import::*;
+
+/// Configuration for batching on a [Pharos](crate::Pharos). See [Pharos::set_batching](crate::Pharos::set_batching).
+///
+/// Events that are sent are kept in a batch instead of going out to the observers right away. Flushing
+/// the pharos only delivers the batch once it holds `max_len` events or `interval` has passed since the first
+/// event was added to it. This trades latency for throughput.
+//
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+//
+pub struct Batching {
+    /// The number of events after which the batch is delivered. The pharos isn't ready for more events
+    /// until a full batch has been delivered.
+    //
+    pub max_len: usize,
+
+    /// How long an event may wait in the batch before it is delivered on the next flush.
+    //
+    pub interval: Duration,
+}
+
+impl Batching {
+    /// Create a new batching configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_len` is zero.
+    //
+    pub fn new(max_len: usize, interval: Duration) -> Self {
+        assert!(max_len > 0, "Batching: max_len must be at least 1");
+
+        Self { max_len, interval }
+    }
+}
+
+/// The events waiting to be delivered.
+//
+#[derive(Debug)]
+//
+pub(crate) struct Batch<Event> {
+    config: Batching,
+    events: VecDeque<Event>,
+
+    // When the oldest event in the batch was added, if we have a timer.
+    //
+    started: Option<Instant>,
+}
+
+impl<Event> Batch<Event> {
+    pub(crate) fn new(config: Batching, events: VecDeque<Event>, now: Option<Instant>) -> Self {
+        let started = if events.is_empty() { None } else { now };
+
+        Self {
+            config,
+            events,
+            started,
+        }
+    }
+
+    pub(crate) fn into_events(self) -> VecDeque<Event> {
+        self.events
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.events.len() >= self.config.max_len
+    }
+
+    pub(crate) fn push(&mut self, evt: Event, now: Option<Instant>) {
+        if self.events.is_empty() {
+            self.started = now;
+        }
+
+        self.events.push_back(evt);
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    // Whether the batch should be delivered. Without a timer, only a full batch is due.
+    //
+    pub(crate) fn is_due(&self, now: Option<Instant>) -> bool {
+        if self.is_full() {
+            return true;
+        }
+
+        match (self.started, now) {
+            (Some(started), Some(now)) => !self.is_empty() && now - started >= self.config.interval,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use super::*;
+
+    #[test]
+    //
+    fn due() {
+        let start = Instant::now();
+        let mut b = Batch::new(
+            Batching::new(3, Duration::from_secs(1)),
+            VecDeque::new(),
+            None,
+        );
+
+        assert!(!b.is_due(Some(start)));
+
+        b.push(1, Some(start));
+        b.push(2, Some(start));
+
+        assert!(!b.is_due(Some(start)));
+        assert!(!b.is_due(None));
+        assert!(b.is_due(Some(start + Duration::from_secs(1))));
+
+        b.push(3, Some(start));
+
+        assert!(b.is_due(None));
+    }
+}
@@ -0,0 +1,32 @@
+/// A small deterministic random number generator (splitmix64) for shuffling the order in which observers
+/// get events. See [Pharos::set_shuffle_seed](crate::Pharos::set_shuffle_seed).
+//
+#[derive(Debug, Clone)]
+//
+pub(crate) struct Shuffle {
+    state: u64,
+}
+
+impl Shuffle {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Fisher-Yates shuffle.
+    //
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+/// Configuration for a circuit breaker on a [Pharos](crate::Pharos). See
+/// [Pharos::set_circuit_breaker](crate::Pharos::set_circuit_breaker).
+///
+/// When slow observers keep the pharos from being ready `trip_after` times in a row, the breaker trips.
+/// While tripped, the pharos reports being ready immediately and the next `cooldown` events are dropped
+/// for all observers. After that the breaker resets and events are delivered normally again.
+///
+/// This protects the producer from being held up by observers that can't keep up, at the cost of losing
+/// events.
+//
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+//
+pub struct CircuitBreaker {
+    /// The number of consecutive times `poll_ready` may return pending before the breaker trips.
+    //
+    pub trip_after: usize,
+
+    /// The number of events to drop once tripped, before delivering events again.
+    //
+    pub cooldown: usize,
+}
+
+impl CircuitBreaker {
+    /// Create a new circuit breaker configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `trip_after` is zero.
+    //
+    pub fn new(trip_after: usize, cooldown: usize) -> Self {
+        assert!(
+            trip_after > 0,
+            "CircuitBreaker: trip_after must be at least 1"
+        );
+
+        Self {
+            trip_after,
+            cooldown,
+        }
+    }
+}
+
+/// The running state of a circuit breaker.
+//
+#[derive(Debug)]
+//
+pub(crate) struct Breaker {
+    config: CircuitBreaker,
+    pending: usize,
+    cooldown_left: usize,
+}
+
+impl Breaker {
+    pub(crate) fn new(config: CircuitBreaker) -> Self {
+        Self {
+            config,
+            pending: 0,
+            cooldown_left: 0,
+        }
+    }
+
+    pub(crate) fn is_tripped(&self) -> bool {
+        self.cooldown_left > 0
+    }
+
+    // Record that the observers were not ready. Returns whether that made us trip.
+    //
+    pub(crate) fn on_pending(&mut self) -> bool {
+        self.pending += 1;
+
+        if self.pending >= self.config.trip_after && self.config.cooldown > 0 {
+            self.pending = 0;
+            self.cooldown_left = self.config.cooldown;
+
+            return true;
+        }
+
+        false
+    }
+
+    pub(crate) fn on_ready(&mut self) {
+        self.pending = 0;
+    }
+
+    // Record that an event was dropped while tripped.
+    //
+    pub(crate) fn on_drop(&mut self) {
+        self.cooldown_left = self.cooldown_left.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use super::*;
+
+    #[test]
+    //
+    fn trip_and_reset() {
+        let mut b = Breaker::new(CircuitBreaker::new(2, 2));
+
+        assert!(!b.on_pending());
+        b.on_ready();
+        assert!(!b.on_pending());
+        assert!(b.on_pending());
+        assert!(b.is_tripped());
+
+        b.on_drop();
+        assert!(b.is_tripped());
+        b.on_drop();
+        assert!(!b.is_tripped());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::import::*;
+use crate::{import::*, BroadcastReport, ObserverId};
 
 /// The error type for errors happening in `pharos`.
 ///
@@ -9,6 +9,8 @@ use crate::import::*;
 pub struct Error {
     pub(crate) inner: Option<Box<dyn ErrorTrait + Send + Sync>>,
     pub(crate) kind: ErrorKind,
+    pub(crate) observers: Vec<(ObserverId, Error)>,
+    pub(crate) report: Option<BroadcastReport>,
 }
 
 impl Error {
@@ -17,11 +19,92 @@ impl Error {
     pub fn kind(&self) -> &ErrorKind {
         &self.kind
     }
+
+    /// Whether this is an error of kind [ErrorKind::Closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        self.kind == ErrorKind::Closed
+    }
+
+    /// Whether this is an error of kind [ErrorKind::SendError].
+    //
+    pub fn is_send_error(&self) -> bool {
+        self.kind == ErrorKind::SendError
+    }
+
+    /// Whether this is an error of kind [ErrorKind::MinChannelSizeOne].
+    //
+    pub fn is_min_channel_size(&self) -> bool {
+        self.kind == ErrorKind::MinChannelSizeOne
+    }
+
+    /// The observers that failed and why, when sending to several observers at once with
+    /// [Pharos::broadcast](crate::Pharos::broadcast) or [Pharos::notify_acked](crate::Pharos::notify_acked).
+    /// Empty for all other errors.
+    //
+    pub fn observers(&self) -> &[(ObserverId, Error)] {
+        &self.observers
+    }
+
+    /// When [Pharos::broadcast](crate::Pharos::broadcast) failed for some observers, the report for the others:
+    /// how many got the event and which of them are lagging. `None` for all other errors.
+    //
+    pub fn report(&self) -> Option<&BroadcastReport> {
+        self.report.as_ref()
+    }
+
+    // Attach the report of a broadcast that failed for some observers.
+    //
+    pub(crate) fn with_report(mut self, report: BroadcastReport) -> Error {
+        self.report = Some(report);
+        self
+    }
+
+    // A SendError listing every observer that failed.
+    //
+    pub(crate) fn failed_observers(observers: Vec<(ObserverId, Error)>) -> Error {
+        Error {
+            inner: None,
+            kind: ErrorKind::SendError,
+            observers,
+            report: None,
+        }
+    }
 }
 
 impl From<ErrorKind> for Error {
     fn from(kind: ErrorKind) -> Error {
-        Error { inner: None, kind }
+        Error {
+            inner: None,
+            kind,
+            observers: Vec::new(),
+            report: None,
+        }
+    }
+}
+
+/// Convert to [std::io::Error], for code that works with `io::Result`. The original error is kept
+/// as the inner error.
+///
+/// - [ErrorKind::Closed] maps to [io::ErrorKind::NotConnected](std::io::ErrorKind::NotConnected)
+/// - [ErrorKind::SendError] maps to [io::ErrorKind::BrokenPipe](std::io::ErrorKind::BrokenPipe)
+/// - [ErrorKind::MinChannelSizeOne], [ErrorKind::SeedExceedsCapacity] and [ErrorKind::ConfigNotCloneable]
+///   map to [io::ErrorKind::InvalidInput](std::io::ErrorKind::InvalidInput)
+/// - [ErrorKind::Timeout] maps to [io::ErrorKind::TimedOut](std::io::ErrorKind::TimedOut)
+//
+impl From<Error> for io::Error {
+    fn from(err: Error) -> io::Error {
+        let kind = match err.kind {
+            ErrorKind::Closed => io::ErrorKind::NotConnected,
+            ErrorKind::SendError => io::ErrorKind::BrokenPipe,
+            ErrorKind::MinChannelSizeOne => io::ErrorKind::InvalidInput,
+            ErrorKind::SeedExceedsCapacity => io::ErrorKind::InvalidInput,
+            ErrorKind::ConfigNotCloneable => io::ErrorKind::InvalidInput,
+            ErrorKind::Timeout => io::ErrorKind::TimedOut,
+            _ => io::ErrorKind::Other,
+        };
+
+        io::Error::new(kind, err)
     }
 }
 
@@ -30,6 +113,8 @@ impl From<FutSendError> for Error {
         Error {
             inner: Some(Box::new(inner)),
             kind: ErrorKind::SendError,
+            observers: Vec::new(),
+            report: None,
         }
     }
 }
@@ -37,11 +122,11 @@ impl From<FutSendError> for Error {
 /// The different kind of errors that can happen when you use the `pharos` API.
 //
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(clippy::manual_non_exhaustive)]
 //
 pub enum ErrorKind {
-    #[doc(hidden)]
-    //
-    //This variant is only used internally.
+    /// Sending an event to an observer failed, because its channel was full or it went away.
+    /// Returned by [Pharos::broadcast](crate::Pharos::broadcast).
     //
     SendError,
 
@@ -50,10 +135,49 @@ pub enum ErrorKind {
     //
     Closed,
 
-    /// The minimum valid buffer size for [`Channel::Bounded`](crate::observable::Channel) is `1`, you sent in `0`.
+    /// The minimum valid buffer size for [`Channel::Bounded`](crate::observable::Channel) and
+    /// [`Channel::RingBuffer`](crate::observable::Channel) is `1`, you sent in `0`.
     //
     MinChannelSizeOne,
 
+    /// The seed passed to [`Pharos::observe_seeded`](crate::Pharos::observe_seeded) has more events than
+    /// fit in the bounded channel of the observer.
+    //
+    SeedExceedsCapacity,
+
+    /// The operation did not complete within the given time.
+    //
+    Timeout,
+
+    /// The operation needs a [Timer](crate::Timer), but none was set. See
+    /// [Pharos::set_timer](crate::Pharos::set_timer).
+    //
+    NoTimer,
+
+    /// The pharos already has as many observers as allowed by
+    /// [Pharos::set_max_observers](crate::Pharos::set_max_observers).
+    //
+    TooManyObservers,
+
+    /// There is no observer with the given [ObserverId](crate::ObserverId). It might have gone away.
+    //
+    UnknownObserver,
+
+    /// The options passed to [Observable::observe_many](crate::Observable::observe_many) can't be copied for
+    /// every observer, because they hold a closure, metadata or a key.
+    //
+    ConfigNotCloneable,
+
+    /// Cloning the event for an observer panicked. The observers that came before already got it, the
+    /// others didn't. The pharos can still be used.
+    //
+    ClonePanicked,
+
+    /// The filter or [transform](crate::ObserveConfig::transform) of an observer panicked, so it was removed.
+    /// Only happens with [Pharos::set_catch_filter_panics](crate::Pharos::set_catch_filter_panics).
+    //
+    ObserverPanicked,
+
     #[doc(hidden)]
     //
     __NonExhaustive__,
@@ -74,19 +198,44 @@ impl PartialEq<ErrorKind> for &ErrorKind {
 impl ErrorTrait for Error {
     fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
         self.inner
-            .as_ref()
-            .map(|e| -> &(dyn ErrorTrait + 'static) { e.deref() })
+            .as_deref()
+            .map(|e| -> &(dyn ErrorTrait + 'static) { e })
     }
 }
 
 impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::SendError => fmt::Display::fmt("Channel closed.", f),
+            Self::SendError => fmt::Display::fmt(
+                "Sending to an observer failed, its channel is full or closed.",
+                f,
+            ),
+            Self::Closed => fmt::Display::fmt("The pharos object is closed.", f),
             Self::MinChannelSizeOne => fmt::Display::fmt(
                 "The minimum valid buffer size for Channel::Bounded is 1, you send in 0.",
                 f,
             ),
+            Self::SeedExceedsCapacity => fmt::Display::fmt(
+                "The seed has more events than fit in the bounded channel.",
+                f,
+            ),
+            Self::Timeout => fmt::Display::fmt("The operation timed out.", f),
+            Self::NoTimer => fmt::Display::fmt("No timer was set on the pharos.", f),
+            Self::TooManyObservers => {
+                fmt::Display::fmt("The pharos has reached the maximum number of observers.", f)
+            }
+            Self::UnknownObserver => fmt::Display::fmt("There is no observer with this id.", f),
+            Self::ConfigNotCloneable => fmt::Display::fmt(
+                "The options hold a closure, metadata or a key, so they can't be cloned.",
+                f,
+            ),
+            Self::ClonePanicked => {
+                fmt::Display::fmt("Cloning the event panicked, not all observers got it.", f)
+            }
+            Self::ObserverPanicked => fmt::Display::fmt(
+                "The filter or transform of an observer panicked, it was removed.",
+                f,
+            ),
 
             _ => unreachable!(),
         }
@@ -100,6 +249,54 @@ impl fmt::Display for Error {
             None => String::new(),
         };
 
-        write!(f, "pharos::Error: {}{}", self.kind, inner)
+        let observers = match self.observers.len() {
+            0 => String::new(),
+            n => format!(" ({} observers failed)", n),
+        };
+
+        write!(f, "pharos::Error: {}{}{}", self.kind, observers, inner)
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use super::*;
+
+    #[test]
+    //
+    fn into_io() {
+        let err: io::Error = Error::from(ErrorKind::Closed).into();
+
+        assert_eq!(io::ErrorKind::NotConnected, err.kind());
+        assert_eq!(
+            "pharos::Error: The pharos object is closed.",
+            &err.to_string()
+        );
+
+        let inner = err.into_inner().expect("inner error");
+
+        assert_eq!(
+            ErrorKind::Closed,
+            inner.downcast_ref::<Error>().expect("pharos::Error").kind()
+        );
+
+        let err: io::Error = Error::from(ErrorKind::MinChannelSizeOne).into();
+
+        assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    }
+
+    #[test]
+    //
+    fn predicates() {
+        let closed = Error::from(ErrorKind::Closed);
+        let send = Error::from(ErrorKind::SendError);
+        let size = Error::from(ErrorKind::MinChannelSizeOne);
+        let other = Error::from(ErrorKind::Timeout);
+
+        assert!(closed.is_closed() && !closed.is_send_error() && !closed.is_min_channel_size());
+        assert!(!send.is_closed() && send.is_send_error() && !send.is_min_channel_size());
+        assert!(!size.is_closed() && !size.is_send_error() && size.is_min_channel_size());
+        assert!(!other.is_closed() && !other.is_send_error() && !other.is_min_channel_size());
     }
 }
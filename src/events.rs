@@ -1,121 +1,1118 @@
-use crate::{import::*, observable::Channel, Error, ErrorKind, Filter, ObserveConfig};
+use crate::{
+    adapters::{
+        BoxedEvents, CombineLatest, EventsReader, Flatten, Inspect, MapWhile, MergedEvents,
+        ReadyChunks, Scan, TakeUntil, Throttle, ThrottlePolicy,
+    },
+    filter::FilterCache,
+    import::*,
+    membership::{announce, Membership, MembershipHub},
+    observable::{Channel, ObserverTransform, Overflow},
+    observer_key::ObserverKey,
+    ring::{ring, RingReceiver, RingSender},
+    tiered::{tiered, TieredReceiver, TieredSender},
+    wake_batch::WakeBatch,
+    Error, ErrorKind, Filter, ObserveConfig, ObserverId, Timer,
+};
+
+/// A stream of events. This is returned from [Observable::observe](crate::Observable::observe).
+/// You will only start receiving events from the moment you call this. Any events in the observed
+/// object emitted before will not be delivered.
+///
+/// For pharos 0.4.2 on x64 Linux: `std::mem::size_of::<Events<_>>() == 88`
+//
+#[derive(Debug)]
+//
+pub struct Events<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    id: ObserverId,
+    rx: Receiver<Event>,
+    closed: bool,
+    terminated: bool,
+    next_seq: u64,
+    last_gap: Option<(u64, u64)>,
+    capacity: Option<usize>,
+    control: Arc<Control>,
+}
+
+impl<Event> Events<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    pub(crate) fn new(id: ObserverId, config: ObserveConfig<Event>) -> (Self, Sender<Event>) {
+        let (tx, rx) = Tx::new(config.channel.unwrap_or_default(), config.reserved);
+
+        Self::assemble(id, tx, rx, config)
+    }
+
+    /// Create a stream of `Event` for an observable that sends `Source` events, converting them on the way in.
+    //
+    pub(crate) fn new_mapped<Source>(
+        id: ObserverId,
+        config: ObserveConfig<Source>,
+    ) -> (Self, Sender<Source>)
+    where
+        Source: Clone + 'static + Sync + Send,
+        Event: From<Source>,
+    {
+        let (tx, rx) = Tx::<Event>::new(config.channel.unwrap_or_default(), config.reserved);
+
+        Self::assemble(id, Tx::Mapped(Box::new(Mapped(tx))), rx, config)
+    }
+
+    fn assemble<Source>(
+        id: ObserverId,
+        tx: Tx<Source>,
+        rx: Receiver<Event>,
+        config: ObserveConfig<Source>,
+    ) -> (Self, Sender<Source>)
+    where
+        Source: Clone + 'static + Sync + Send,
+    {
+        let control = Arc::new(Control::default());
+
+        let capacity = match config.channel {
+            Some(Channel::Bounded(size)) | Some(Channel::RingBuffer(size)) => Some(size),
+            _ => None,
+        };
+
+        let tx = Sender {
+            id,
+            tx,
+            filter: config.filter,
+            shared_filter: config.shared_filter.map(|f| f.key()),
+            metadata: config.metadata,
+            key: config.key,
+            priority: config.priority,
+            is_high: config.is_high,
+            overflow: config.overflow,
+            transform: config.transform,
+            max_errors: config.max_errors,
+            errors: 0,
+            failures: VecDeque::new(),
+            membership: None,
+            released: None,
+            control: control.clone(),
+            peak: 0,
+            dropped: 0,
+            backlog_limit: None,
+            next_seq: 0,
+        };
+
+        (
+            Self {
+                id,
+                rx,
+                closed: false,
+                terminated: false,
+                next_seq: 0,
+                last_gap: None,
+                capacity,
+                control,
+            },
+            tx,
+        )
+    }
+
+    /// The id of this observer, eg. for [Pharos::observer_metadata](crate::Pharos::observer_metadata).
+    //
+    pub fn id(&self) -> ObserverId {
+        self.id
+    }
+
+    /// The most recent point where events were missed, as `(expected, received)` sequence numbers. Every
+    /// event sent to this observer is numbered, starting at `0`. When events are lost, like with a
+    /// [Channel::RingBuffer] that overflows, a bounded channel that is full or while the observer is
+    /// [paused](Events::pause), the next event that arrives doesn't have the expected number. In that case
+    /// `received - expected` events were missed. Events that were filtered out don't count.
+    //
+    pub fn last_gap(&self) -> Option<(u64, u64)> {
+        self.last_gap
+    }
+
+    /// The number of events the channel of this subscription can hold, eg. to size buffers on the
+    /// consumer side. `None` for an unbounded channel. Slots reserved for high priority events with
+    /// [ObserveConfig::reserve] come on top of this.
+    //
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Disconnect from the observable object. This way the sender will stop sending new events
+    /// and you can still continue to read any events that are still pending in the channel.
+    ///
+    /// This is a half-close from the consumer side: the producer skips this observer from now on,
+    /// but the events that are already queued are kept. Keep polling to drain them, the stream ends
+    /// with `None` once they are consumed. Drop the [Events] instead if you don't want them.
+    //
+    pub fn close(&mut self) {
+        self.rx.close();
+        self.closed = true;
+    }
+
+    /// The same as [Events::close], named for the consumer side half-close: the producer stops sending
+    /// to this observer, while the events that are already queued can still be read.
+    //
+    pub fn stop_receiving(&mut self) {
+        self.close();
+    }
+
+    /// Poll for the next event, without having to import [Stream]. This is what the [Stream] impl calls,
+    /// for driving the subscription manually from your own futures or state machines.
+    //
+    pub fn poll_event(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        self.poll_next(cx)
+    }
+
+    /// Poll based version of [Events::close]. Closing never has to wait, so this always resolves
+    /// right away. Events that are still queued can be read with [Events::poll_event] afterwards.
+    //
+    pub fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        self.get_mut().close();
+
+        Poll::Ready(())
+    }
+
+    /// Whether this stream was closed with [Events::close] or has ended because the observable
+    /// went away. Note that if the observable has gone, this only becomes true once the stream
+    /// has returned `None`.
+    //
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Wait for the observable to shut down, that is until the pharos is closed or dropped, or
+    /// it has removed this observer. This does not consume any events, so those that are
+    /// still queued can be read afterwards.
+    ///
+    /// Only the task that polled most recently will be woken, so don't await several of these
+    /// futures for the same stream concurrently.
+    //
+    pub fn wait_closed(&self) -> impl Future<Output = ()> + '_ {
+        poll_fn(move |cx| {
+            self.control.waker.register(cx.waker());
+
+            if self.control.closed.load(Ordering::Acquire) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+    }
+
+    /// Consume all events until the stream ends and return how many there were. Like
+    /// [StreamExt::count](futures::StreamExt::count), without having to import it.
+    //
+    pub async fn count(self) -> usize {
+        StreamExt::count(self).await
+    }
+
+    /// Consume all events until the stream ends and return them, eg. when the observable closes.
+    /// Like [StreamExt::collect](futures::StreamExt::collect), without having to import it or name
+    /// the collection.
+    //
+    pub async fn collect_until_close(self) -> Vec<Event> {
+        StreamExt::collect(self).await
+    }
+
+    /// Send all events into `sink` until the stream ends, then close the sink. Like
+    /// [StreamExt::forward](futures::StreamExt::forward), but for the events of this subscription.
+    ///
+    /// If the sink fails, the error is returned without closing the sink. This subscription is dropped,
+    /// so the observable stops sending to it.
+    //
+    pub async fn forward_to<S>(mut self, sink: S) -> Result<(), S::Error>
+    where
+        S: Sink<Event>,
+    {
+        pin_mut!(sink);
+
+        while let Some(evt) = self.next().await {
+            sink.send(evt).await?;
+        }
+
+        sink.close().await
+    }
+
+    /// Wait for the first event on any of `subscriptions`, then close them all. `None` if they all end
+    /// without an event. Like [select_all](futures::future::select_all), for when you only need one event.
+    //
+    pub async fn race_first(mut subscriptions: Vec<Events<Event>>) -> Option<Event> {
+        let evt = poll_fn(|cx| {
+            let mut pending = false;
+
+            for events in &mut subscriptions {
+                match Pin::new(events).poll_next(cx) {
+                    Poll::Ready(Some(evt)) => return Poll::Ready(Some(evt)),
+                    Poll::Ready(None) => {}
+                    Poll::Pending => pending = true,
+                }
+            }
+
+            if pending {
+                Poll::Pending
+            } else {
+                Poll::Ready(None)
+            }
+        })
+        .await;
+
+        subscriptions.iter_mut().for_each(Events::close);
+
+        evt
+    }
+
+    /// Ask the observable to skip this observer until [Events::resume] is called. Events sent in the
+    /// meantime are not delivered to this stream and are lost. Events that were already queued can still
+    /// be read.
+    ///
+    /// Note that a paused observer with a full bounded channel still exerts back pressure on the
+    /// producer until those queued events are read.
+    //
+    pub fn pause(&self) {
+        self.control.paused.store(true, Ordering::Release);
+    }
+
+    /// Start receiving events again after [Events::pause].
+    //
+    pub fn resume(&self) {
+        self.control.paused.store(false, Ordering::Release);
+    }
+
+    /// Whether this observer is paused. See [Events::pause].
+    //
+    pub fn is_paused(&self) -> bool {
+        self.control.paused.load(Ordering::Acquire)
+    }
+
+    /// Call `f` with a reference to every event, eg. for logging or metrics, while passing the events on
+    /// unchanged. Like [StreamExt::inspect](futures::StreamExt::inspect), but the returned stream can still be closed.
+    //
+    pub fn inspect<F>(self, f: F) -> Inspect<Event, F>
+    where
+        F: FnMut(&Event),
+    {
+        Inspect::new(self, f)
+    }
+
+    /// Interleave the events of this subscription with those of `other`, eg. another pharos or the
+    /// same one with a different filter. The merged stream ends once both have ended and
+    /// [closing](MergedEvents::close) it closes both subscriptions.
+    //
+    pub fn merge(self, other: Events<Event>) -> MergedEvents<Event> {
+        MergedEvents::new(self, other)
+    }
+
+    /// Combine this subscription with `other`, eg. two streams of state for a UI. Every time either of them
+    /// has a new event, the combined stream yields the latest event of both, once both have sent one. It ends
+    /// as soon as either subscription ends, closing the other one. [Closing](CombineLatest::close) it closes
+    /// both subscriptions.
+    //
+    pub fn combine_latest<Other>(self, other: Events<Other>) -> CombineLatest<Event, Other>
+    where
+        Other: Clone + 'static + Sync + Send,
+    {
+        CombineLatest::new(self, other)
+    }
+
+    /// Erase the type of this subscription, like [StreamExt::boxed](futures::StreamExt::boxed), but keep
+    /// being able to close it. The adapters that yield the events unchanged can be boxed as well, so
+    /// different kinds of subscriptions can be stored together.
+    //
+    pub fn boxed(self) -> BoxedEvents<Event> {
+        BoxedEvents::new(self)
+    }
+
+    /// Yield at most one event per `min_interval`, measured with `timer`. `policy` decides what happens to
+    /// the events that arrive in between. The returned stream can still be closed.
+    //
+    pub fn throttle(
+        self,
+        min_interval: Duration,
+        policy: ThrottlePolicy,
+        timer: Arc<dyn Timer>,
+    ) -> Throttle<Event> {
+        Throttle::new(self, min_interval, policy, timer)
+    }
+
+    /// Read the bytes of the events as one continuous byte stream, eg. to feed them to something that takes an
+    /// [AsyncRead](futures_io::AsyncRead), like an HTTP body. Reads return bytes from at most one event, keeping
+    /// what doesn't fit in the buffer for the next read. Reading returns `0` bytes once the stream has ended.
+    //
+    pub fn into_async_read(self) -> EventsReader<Event>
+    where
+        Event: AsRef<[u8]>,
+    {
+        EventsReader::new(self)
+    }
+
+    /// Batch all events that are immediately available, up to `cap` events per batch. Unlike the
+    /// generic adapter from `futures`, this never waits for a batch to fill up and the returned stream
+    /// still lets you [close](ReadyChunks::close) the subscription.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cap` is zero.
+    //
+    pub fn ready_chunks(self, cap: usize) -> ReadyChunks<Event> {
+        ReadyChunks::new(self, cap)
+    }
+
+    /// Transform events with a closure that has access to some state, like running totals or a state
+    /// machine. The stream ends when the closure returns `None`, at which point the subscription is closed.
+    /// Like [StreamExt::scan](futures::StreamExt::scan), but the returned stream can still be closed.
+    //
+    pub fn scan<S, F, U>(self, init: S, f: F) -> Scan<Event, S, F>
+    where
+        F: FnMut(&mut S, Event) -> Option<U>,
+    {
+        Scan::new(self, init, f)
+    }
+
+    /// Yield events until `until` resolves, eg. a shutdown signal, then end the stream and close the
+    /// subscription. Events that are still queued at that point are discarded. Unlike
+    /// [StreamExt::take_until](futures::StreamExt::take_until), this closes the subscription, so the
+    /// observable stops sending to it.
+    //
+    pub fn take_until<Fut>(self, until: Fut) -> TakeUntil<Event, Fut>
+    where
+        Fut: Future,
+    {
+        TakeUntil::new(self, until)
+    }
+
+    /// Transform events until the closure returns `None`, eg. on a terminal event. At that point the
+    /// stream ends and the subscription is closed. Like [Iterator::map_while], but for the events of this
+    /// subscription.
+    //
+    pub fn map_while<F, U>(self, f: F) -> MapWhile<Event, F>
+    where
+        F: FnMut(Event) -> Option<U>,
+    {
+        MapWhile::new(self, f)
+    }
+
+    /// For events that are streams themselves, yield the items of each of them in turn. Like
+    /// [StreamExt::flatten](futures::StreamExt::flatten), but the subscription can still be closed.
+    ///
+    /// Events have to be [Clone], so this is for streams like [futures::stream::Iter] rather than
+    /// other subscriptions.
+    //
+    pub fn flatten(self) -> Flatten<Event>
+    where
+        Event: Stream + Unpin,
+    {
+        Flatten::new(self)
+    }
+}
+
+// Just forward
+//
+impl<Event> Stream for Events<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            let env = match ready!(Pin::new(&mut self.rx).poll_next(cx)) {
+                Some(env) => env,
+
+                None => {
+                    self.closed = true;
+                    self.terminated = true;
+                    self.control.drained.wake();
+
+                    return Poll::Ready(None);
+                }
+            };
+
+            if self.control.depth.fetch_sub(1, Ordering::AcqRel) == 1 {
+                self.control.drained.wake();
+            }
+
+            if env.seq != self.next_seq {
+                self.last_gap = Some((self.next_seq, env.seq));
+            }
+
+            self.next_seq = env.seq + 1;
+
+            // Skip events that are past their deadline. They don't count as a gap and are acknowledged
+            // as skipped.
+            //
+            if !env.is_expired() {
+                return Poll::Ready(Some(env.open()));
+            }
+
+            env.expire();
+        }
+    }
+}
+
+// Nobody is going to take the queued events anymore.
+//
+impl<Event> Drop for Events<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn drop(&mut self) {
+        self.control.drained.wake();
+    }
+}
+
+// Only terminated once the stream returned `None`. After [Events::close] there might still be
+// queued events.
+//
+impl<Event> FusedStream for Events<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+/// What actually travels over the channel. Next to the event, this can carry a channel
+/// to let the producer know that the consumer has dequeued the event, `true`, or skipped it
+/// because it expired, `false`.
+//
+pub(crate) struct Envelope<Event> {
+    event: Event,
+    ack: Option<oneshot::Sender<bool>>,
+
+    // Numbers the events sent to an observer, so it can tell when it missed some.
+    //
+    seq: u64,
+    deadline: Option<Deadline>,
+}
+
+/// When an event goes stale, see [Pharos::set_ttl](crate::Pharos::set_ttl). Carries the timer so the
+/// consumer can tell the time.
+//
+#[derive(Clone)]
+//
+pub(crate) struct Deadline {
+    at: Instant,
+    timer: Arc<dyn Timer>,
+}
+
+impl Deadline {
+    pub(crate) fn new(at: Instant, timer: Arc<dyn Timer>) -> Self {
+        Self { at, timer }
+    }
+
+    fn has_passed(&self) -> bool {
+        self.timer.now() >= self.at
+    }
+}
+
+impl<Event> Envelope<Event> {
+    pub(crate) fn new(event: Event, ack: Option<oneshot::Sender<bool>>) -> Self {
+        Self {
+            event,
+            ack,
+            seq: 0,
+            deadline: None,
+        }
+    }
+
+    pub(crate) fn with_deadline(mut self, deadline: Option<Deadline>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    fn is_expired(&self) -> bool {
+        matches!(&self.deadline, Some(deadline) if deadline.has_passed())
+    }
+
+    // Acknowledge reception if the producer asked for it and hand out the event.
+    //
+    fn map<To>(self, f: impl FnOnce(Event) -> To) -> Envelope<To> {
+        Envelope {
+            event: f(self.event),
+            ack: self.ack,
+            seq: self.seq,
+            deadline: self.deadline,
+        }
+    }
+
+    fn open(self) -> Event {
+        if let Some(ack) = self.ack {
+            // If the producer is no longer waiting, there is nobody to tell.
+            //
+            let _ = ack.send(true);
+        }
+
+        self.event
+    }
+
+    // Let the producer know the event was skipped because it expired.
+    //
+    fn expire(self) {
+        if let Some(ack) = self.ack {
+            let _ = ack.send(false);
+        }
+    }
+}
+
+/// The sender of the channel.
+/// For pharos 0.4.2 on x64 Linux: `std::mem::size_of::<Sender<_>>() == 296`
+//
+pub(crate) struct Sender<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    id: ObserverId,
+    tx: Tx<Event>,
+    filter: Option<Filter<Event>>,
+
+    // The key of the shared filter, if `filter` calls one. The filter holds on to the shared predicate, so
+    // the key stays unique while this sender lives.
+    //
+    shared_filter: Option<usize>,
+    metadata: Option<Box<dyn Any + Send + Sync>>,
+    key: Option<ObserverKey>,
+    priority: i32,
+
+    // Tells which events may use the reserved slots of a tiered channel.
+    //
+    is_high: Option<fn(&Event) -> bool>,
+
+    // Gets the events that don't fit in the channel. When set, we never make the producer wait.
+    //
+    overflow: Option<Overflow<Event>>,
+
+    // Changes events before they go out to this observer, see ObserveConfig::transform.
+    //
+    transform: Option<ObserverTransform<Event>>,
+
+    // How many times sending may fail within a window before we give up on this observer. Without a
+    // timer, `errors` counts the failures in a row, otherwise `failures` holds when the recent ones happened.
+    //
+    max_errors: Option<(usize, Duration)>,
+    errors: usize,
+    failures: VecDeque<Instant>,
+
+    // Where to announce that we left, if anyone is watching.
+    //
+    membership: Option<MembershipHub>,
+
+    // Set when the SubscriptionGuard for this observer is dropped.
+    //
+    released: Option<Arc<AtomicBool>>,
+
+    // Lets the Events know when we go away and lets them pause us.
+    //
+    control: Arc<Control>,
+
+    // The high-water mark of `control.depth`.
+    //
+    peak: usize,
+
+    // Events this observer lost, because a ring buffer evicted them or the channel was full.
+    //
+    dropped: u64,
+
+    // How many queued events are fine, see Pharos::observe_with_backlog_limit.
+    //
+    backlog_limit: Option<usize>,
+
+    // The sequence number of the next event we send.
+    //
+    next_seq: u64,
+}
+
+impl<Event> Drop for Sender<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn drop(&mut self) {
+        self.control.signal();
+
+        if let Some(hub) = &self.membership {
+            announce(hub, Membership::Left(self.id));
+        }
+    }
+}
+
+/// Shared between [Sender] and [Events] so the latter can be notified when the
+/// observable shuts down, and can ask not to be sent events for a while.
+//
+#[derive(Default)]
+//
+struct Control {
+    closed: AtomicBool,
+    waker: AtomicWaker,
+    paused: AtomicBool,
+
+    // The number of events queued in the channel.
+    //
+    depth: AtomicUsize,
+
+    // Woken when the receiver took the last queued event or went away.
+    //
+    drained: AtomicWaker,
+}
+
+impl Control {
+    fn signal(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+}
+
+impl fmt::Debug for Control {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pharos::events::Control {{ closed: {}, paused: {} }}",
+            self.closed.load(Ordering::Acquire),
+            self.paused.load(Ordering::Acquire),
+        )
+    }
+}
+
+/// The different channel types we can send on.
+//
+enum Tx<Event> {
+    Bounded(FutSender<Envelope<Event>>),
+    Unbounded(FutUnboundedSender<Envelope<Event>>),
+    Ring(RingSender<Envelope<Event>>),
+    Tiered(TieredSender<Envelope<Event>>),
+    Mapped(Box<dyn MapTx<Event>>),
+}
+
+impl<Event> Sender<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    // Verify whether this observer is still around.
+    //
+    pub(crate) fn is_closed(&self) -> bool {
+        let released = match &self.released {
+            Some(flag) => flag.load(Ordering::Acquire),
+            None => false,
+        };
+
+        released || self.tx.is_closed()
+    }
+
+    pub(crate) fn id(&self) -> ObserverId {
+        self.id
+    }
+
+    pub(crate) fn metadata(&self) -> Option<&(dyn Any + Send + Sync)> {
+        self.metadata.as_deref()
+    }
+
+    pub(crate) fn key(&self) -> Option<&ObserverKey> {
+        self.key.as_ref()
+    }
+
+    pub(crate) fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub(crate) fn set_membership(&mut self, hub: MembershipHub) {
+        self.membership = Some(hub);
+    }
+
+    // Stop announcing that we left, eg. because another sender takes over our id.
+    //
+    pub(crate) fn take_membership(&mut self) -> Option<MembershipHub> {
+        self.membership.take()
+    }
+
+    // Whether the consumer has asked us not to send events for now.
+    //
+    pub(crate) fn is_paused(&self) -> bool {
+        self.control.paused.load(Ordering::Acquire)
+    }
+
+    /// If this observer has an overflow callback and no room in its channel, hand the event to the
+    /// callback. Otherwise the event is given back.
+    //
+    pub(crate) fn try_overflow<'a>(&mut self, evt: Cow<'a, Event>) -> Result<(), Cow<'a, Event>> {
+        let overflow = match &mut self.overflow {
+            Some(overflow) => overflow,
+            None => return Err(evt),
+        };
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        match self.tx.poll_ready(cx) {
+            Poll::Pending => {
+                let evt = match &mut self.transform {
+                    Some(transform) => transform.call(evt.into_owned()),
+                    None => evt.into_owned(),
+                };
+
+                overflow.call(evt);
+                Ok(())
+            }
+
+            _ => Err(evt),
+        }
+    }
+
+    /// Check whether this sender is interested in this event.
+    //
+    pub(crate) fn filter(&mut self, evt: &Event) -> bool {
+        match &mut self.filter {
+            Some(f) => f.call(evt),
+            None => true,
+        }
+    }
+
+    /// Like [Sender::filter], but shared filters only run once per event.
+    //
+    pub(crate) fn filter_cached(&mut self, evt: &Event, cache: &mut FilterCache) -> bool {
+        match (self.shared_filter, &mut self.filter) {
+            (Some(key), Some(f)) => cache.get_or_call(key, || f.call(evt)),
+            (_, Some(f)) => f.call(evt),
+            (_, None) => true,
+        }
+    }
+
+    /// Tie the lifetime of this observer to a [SubscriptionGuard].
+    //
+    pub(crate) fn guard(&mut self) -> SubscriptionGuard {
+        let flag = Arc::new(AtomicBool::new(false));
+
+        self.released = Some(flag.clone());
+
+        SubscriptionGuard { released: flag }
+    }
+
+    /// Send an envelope, which lets the caller ask for an acknowledgment. With a `batch`, waking the
+    /// receiver is left to the batch where the channel allows it.
+    //
+    pub(crate) fn start_send_envelope(
+        &mut self,
+        mut envelope: Envelope<Event>,
+        batch: Option<&mut WakeBatch>,
+    ) -> Result<(), Error> {
+        envelope.seq = self.next_seq;
+
+        if let Some(transform) = &mut self.transform {
+            envelope.event = transform.call(envelope.event);
+        }
+
+        let high = matches!(self.is_high, Some(is_high) if is_high(&envelope.event));
+
+        // Count the event before it can be received, so the receiver never takes the depth below zero.
+        //
+        let mut depth = self.control.depth.fetch_add(1, Ordering::AcqRel) + 1;
+
+        let res = match self.tx.start_send(envelope, high, batch) {
+            Ok(false) => Ok(()),
+
+            Ok(true) => {
+                self.control.depth.fetch_sub(1, Ordering::AcqRel);
+                depth -= 1;
+                self.dropped += 1;
+                Ok(())
+            }
+
+            Err(e) => Err(e),
+        };
+
+        match res {
+            Ok(()) => {
+                self.peak = self.peak.max(depth);
+                self.next_seq += 1;
+                self.errors = 0;
+            }
+
+            Err(_) => {
+                self.control.depth.fetch_sub(1, Ordering::AcqRel);
+
+                // The event is lost, the receiver sees a gap in the sequence numbers.
+                //
+                if !self.tx.is_closed() {
+                    self.dropped += 1;
+                    self.next_seq += 1;
+                }
+            }
+        }
 
-/// A stream of events. This is returned from [Observable::observe](crate::Observable::observe).
-/// You will only start receiving events from the moment you call this. Any events in the observed
-/// object emitted before will not be delivered.
-///
-/// For pharos 0.4.0 on x64 Linux: `std::mem::size_of::<Events<_>>() == 16`
-//
-#[derive(Debug)]
-//
-pub struct Events<Event>
-where
-    Event: Clone + 'static + Sync + Send,
-{
-    rx: Receiver<Event>,
+        res
+    }
+
+    /// The highest number of events that were queued at once since creation or the last reset.
+    //
+    pub(crate) fn peak_depth(&self) -> usize {
+        self.peak
+    }
+
+    /// Start tracking the peak again from the current depth.
+    //
+    pub(crate) fn reset_peak(&mut self) {
+        self.peak = self.control.depth.load(Ordering::Acquire);
+    }
+
+    /// Count a failed send that happened at `now`, if there is a timer. Returns whether this observer has
+    /// now failed more often than allowed.
+    //
+    pub(crate) fn count_error(&mut self, now: Option<Instant>) -> bool {
+        let (max, window) = match self.max_errors {
+            Some(limit) => limit,
+            None => return false,
+        };
+
+        let now = match now {
+            Some(now) => now,
+
+            None => {
+                self.errors += 1;
+                return self.errors > max;
+            }
+        };
+
+        while matches!(self.failures.front(), Some(&at) if now.duration_since(at) >= window) {
+            self.failures.pop_front();
+        }
+
+        self.failures.push_back(now);
+
+        self.failures.len() > max
+    }
+
+    /// The number of events dropped since the last call.
+    //
+    pub(crate) fn take_dropped(&mut self) -> u64 {
+        mem::take(&mut self.dropped)
+    }
+
+    pub(crate) fn set_backlog_limit(&mut self, limit: usize) {
+        self.backlog_limit = Some(limit);
+    }
+
+    /// Count an event this observer doesn't get because it is paused, so the receiver sees a gap.
+    //
+    pub(crate) fn skip_paused(&mut self) {
+        self.next_seq += 1;
+    }
+
+    /// Ready once the receiver took every event that is queued. Fails if it went away.
+    //
+    pub(crate) fn poll_drained(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.control.drained.register(cx.waker());
+
+        if self.tx.is_closed() {
+            return Poll::Ready(Err(ErrorKind::Closed.into()));
+        }
+
+        if self.control.depth.load(Ordering::Acquire) == 0 {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Whether more events are queued than the backlog limit allows.
+    //
+    pub(crate) fn is_lagging(&self) -> bool {
+        matches!(self.backlog_limit, Some(limit) if self.control.depth.load(Ordering::Acquire) > limit)
+    }
 }
 
-impl<Event> Events<Event>
+impl<Event> Tx<Event>
 where
     Event: Clone + 'static + Sync + Send,
 {
-    pub(crate) fn new(config: ObserveConfig<Event>) -> (Self, Sender<Event>) {
-        let (tx, rx) = match config.channel {
+    fn new(channel: Channel, reserved: usize) -> (Self, Receiver<Event>) {
+        match channel {
+            Channel::Bounded(queue_size) if reserved > 0 => {
+                let (tx, rx) = tiered(queue_size, reserved);
+
+                (Tx::Tiered(tx), Receiver::Tiered { rx })
+            }
+
             Channel::Bounded(queue_size) => {
                 let (tx, rx) = mpsc::channel(queue_size - 1);
 
-                (
-                    Sender::Bounded {
-                        tx,
-                        filter: config.filter,
-                    },
-                    Receiver::Bounded { rx },
-                )
+                (Tx::Bounded(tx), Receiver::Bounded { rx })
             }
 
             Channel::Unbounded => {
                 let (tx, rx) = mpsc::unbounded();
 
-                (
-                    Sender::Unbounded {
-                        tx,
-                        filter: config.filter,
-                    },
-                    Receiver::Unbounded { rx },
-                )
+                (Tx::Unbounded(tx), Receiver::Unbounded { rx })
+            }
+
+            Channel::RingBuffer(size) => {
+                let (tx, rx) = ring(size);
+
+                (Tx::Ring(tx), Receiver::Ring { rx })
             }
 
             _ => unreachable!(),
-        };
+        }
+    }
+}
 
-        (Self { rx }, tx)
+impl<Event> Tx<Event> {
+    fn is_closed(&self) -> bool {
+        match self {
+            Tx::Bounded(tx) => tx.is_closed(),
+            Tx::Unbounded(tx) => tx.is_closed(),
+            Tx::Ring(tx) => tx.is_closed(),
+            Tx::Tiered(tx) => tx.is_closed(),
+            Tx::Mapped(tx) => tx.is_closed(),
+        }
     }
 
-    /// Disconnect from the observable object. This way the sender will stop sending new events
-    /// and you can still continue to read any events that are still pending in the channel.
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        match self {
+            Tx::Bounded(tx) => Pin::new(tx).poll_ready(cx).map_err(Into::into),
+            Tx::Unbounded(tx) => Pin::new(tx).poll_ready(cx).map_err(Into::into),
+
+            // A ring never has to wait, it drops the oldest event instead.
+            //
+            Tx::Ring(tx) if tx.is_closed() => Poll::Ready(Err(ErrorKind::Closed.into())),
+            Tx::Ring(_) => Poll::Ready(Ok(())),
+
+            Tx::Tiered(tx) if tx.is_closed() => Poll::Ready(Err(ErrorKind::Closed.into())),
+            Tx::Tiered(tx) => tx.poll_ready(cx).map(Ok),
+
+            Tx::Mapped(tx) => tx.poll_ready(cx),
+        }
+    }
+
+    // Returns whether an older event was dropped to make room. `high` tells whether the event may use
+    // reserved slots.
     //
-    pub fn close(&mut self) {
-        self.rx.close();
+    fn start_send(
+        &mut self,
+        envelope: Envelope<Event>,
+        high: bool,
+        batch: Option<&mut WakeBatch>,
+    ) -> Result<bool, Error> {
+        match self {
+            Tx::Bounded(tx) => Pin::new(tx)
+                .start_send(envelope)
+                .map(|_| false)
+                .map_err(Into::into),
+            Tx::Unbounded(tx) => Pin::new(tx)
+                .start_send(envelope)
+                .map(|_| false)
+                .map_err(Into::into),
+
+            Tx::Ring(tx) => {
+                let res = match batch {
+                    Some(batch) => tx.send_deferred(envelope, batch),
+                    None => tx.send(envelope),
+                };
+
+                match res {
+                    Ok(evicted) => Ok(evicted.is_some()),
+                    Err(_) => Err(ErrorKind::SendError.into()),
+                }
+            }
+
+            // Full or closed.
+            //
+            Tx::Tiered(tx) => {
+                let res = match batch {
+                    Some(batch) => tx.send_deferred(envelope, high, batch),
+                    None => tx.send(envelope, high),
+                };
+
+                match res {
+                    Ok(()) => Ok(false),
+                    Err(_) => Err(ErrorKind::SendError.into()),
+                }
+            }
+
+            Tx::Mapped(tx) => tx.start_send(envelope, high, batch),
+        }
     }
-}
 
-// Just forward
-//
-impl<Event> Stream for Events<Event>
-where
-    Event: Clone + 'static + Sync + Send,
-{
-    type Item = Event;
+    fn poll_close(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        match self {
+            Tx::Bounded(tx) => Pin::new(tx).poll_close(cx).map_err(Into::into),
+            Tx::Unbounded(tx) => Pin::new(tx).poll_close(cx).map_err(Into::into),
 
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.rx).poll_next(cx)
+            Tx::Ring(tx) => {
+                tx.close();
+                Poll::Ready(Ok(()))
+            }
+
+            Tx::Tiered(tx) => {
+                tx.close();
+                Poll::Ready(Ok(()))
+            }
+
+            Tx::Mapped(tx) => tx.poll_close(cx),
+        }
     }
 }
 
-/// The sender of the channel.
-/// For pharos 0.4.0 on x64 Linux: `std::mem::size_of::<Sender<_>>() == 56`
+// A channel for events of another type, for Pharos::observe_into. Hides the type of the events
+// in the channel from the Sender.
 //
-pub(crate) enum Sender<Event>
-where
-    Event: Clone + 'static + Sync + Send,
-{
-    Bounded {
-        tx: FutSender<Event>,
-        filter: Option<Filter<Event>>,
-    },
-    Unbounded {
-        tx: FutUnboundedSender<Event>,
-        filter: Option<Filter<Event>>,
-    },
+trait MapTx<Event>: Send + Sync {
+    fn is_closed(&self) -> bool;
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>>;
+    fn start_send(
+        &mut self,
+        envelope: Envelope<Event>,
+        high: bool,
+        batch: Option<&mut WakeBatch>,
+    ) -> Result<bool, Error>;
+    fn poll_close(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>>;
 }
 
-impl<Event> Sender<Event>
+struct Mapped<To>(Tx<To>);
+
+impl<Event, To> MapTx<Event> for Mapped<To>
 where
-    Event: Clone + 'static + Sync + Send,
+    To: From<Event> + Clone + 'static + Sync + Send,
 {
-    // Verify whether this observer is still around.
-    //
-    pub(crate) fn is_closed(&self) -> bool {
-        match self {
-            Sender::Bounded { tx, .. } => tx.is_closed(),
-            Sender::Unbounded { tx, .. } => tx.is_closed(),
-        }
+    fn is_closed(&self) -> bool {
+        self.0.is_closed()
     }
 
-    /// Check whether this sender is interested in this event.
-    //
-    pub(crate) fn filter(&mut self, evt: &Event) -> bool {
-        match self {
-            Sender::Bounded { filter, .. } => Self::filter_inner(filter, evt),
-            Sender::Unbounded { filter, .. } => Self::filter_inner(filter, evt),
-        }
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.0.poll_ready(cx)
     }
 
-    fn filter_inner(filter: &mut Option<Filter<Event>>, evt: &Event) -> bool {
-        match filter {
-            Some(f) => f.call(evt),
-            None => true,
-        }
+    fn start_send(
+        &mut self,
+        envelope: Envelope<Event>,
+        high: bool,
+        batch: Option<&mut WakeBatch>,
+    ) -> Result<bool, Error> {
+        self.0.start_send(envelope.map(To::from), high, batch)
+    }
+
+    fn poll_close(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.0.poll_close(cx)
+    }
+}
+
+/// Keeps a subscription alive. Returned by [Pharos::subscribe_weak](crate::Pharos::subscribe_weak).
+///
+/// When this is dropped, the observer will be removed the next time the pharos notices closed
+/// observers, that is when sending an event or calling [Pharos::num_observers](crate::Pharos::num_observers).
+/// From then on, the [Events] stream will end after the events that were already queued.
+//
+#[derive(Debug)]
+//
+pub struct SubscriptionGuard {
+    released: Arc<AtomicBool>,
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        self.released.store(true, Ordering::Release);
     }
 }
 
@@ -125,8 +1122,18 @@ enum Receiver<Event>
 where
     Event: Clone + 'static + Sync + Send,
 {
-    Bounded { rx: FutReceiver<Event> },
-    Unbounded { rx: FutUnboundedReceiver<Event> },
+    Bounded {
+        rx: FutReceiver<Envelope<Event>>,
+    },
+    Unbounded {
+        rx: FutUnboundedReceiver<Envelope<Event>>,
+    },
+    Ring {
+        rx: RingReceiver<Envelope<Event>>,
+    },
+    Tiered {
+        rx: TieredReceiver<Envelope<Event>>,
+    },
 }
 
 impl<Event> Receiver<Event>
@@ -137,6 +1144,8 @@ where
         match self {
             Receiver::Bounded { rx } => rx.close(),
             Receiver::Unbounded { rx } => rx.close(),
+            Receiver::Ring { rx } => rx.close(),
+            Receiver::Tiered { rx } => rx.close(),
         };
     }
 }
@@ -157,6 +1166,16 @@ where
                 "pharos::events::Receiver::<{}>::Unbounded(_)",
                 type_name::<Event>()
             ),
+            Self::Ring { .. } => write!(
+                f,
+                "pharos::events::Receiver::<{}>::Ring(_)",
+                type_name::<Event>()
+            ),
+            Self::Tiered { .. } => write!(
+                f,
+                "pharos::events::Receiver::<{}>::Tiered(_)",
+                type_name::<Event>()
+            ),
         }
     }
 }
@@ -165,12 +1184,14 @@ impl<Event> Stream for Receiver<Event>
 where
     Event: Clone + 'static + Sync + Send,
 {
-    type Item = Event;
+    type Item = Envelope<Event>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         match self.get_mut() {
             Receiver::Bounded { rx } => Pin::new(rx).poll_next(cx),
             Receiver::Unbounded { rx } => Pin::new(rx).poll_next(cx),
+            Receiver::Ring { rx } => Pin::new(rx).poll_next(cx),
+            Receiver::Tiered { rx } => Pin::new(rx).poll_next(cx),
         }
     }
 }
@@ -182,17 +1203,20 @@ where
     type Error = Error;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        match self.get_mut() {
-            Sender::Bounded { tx, .. } => Pin::new(tx).poll_ready(cx).map_err(Into::into),
-            Sender::Unbounded { tx, .. } => Pin::new(tx).poll_ready(cx).map_err(Into::into),
+        let this = self.get_mut();
+
+        match this.tx.poll_ready(cx) {
+            // Events that don't fit go to the overflow callback, so we are always ready.
+            //
+            Poll::Pending if this.overflow.is_some() => Poll::Ready(Ok(())),
+
+            ready => ready,
         }
     }
 
     fn start_send(self: Pin<&mut Self>, item: Event) -> Result<(), Self::Error> {
-        match self.get_mut() {
-            Sender::Bounded { tx, .. } => Pin::new(tx).start_send(item).map_err(Into::into),
-            Sender::Unbounded { tx, .. } => Pin::new(tx).start_send(item).map_err(Into::into),
-        }
+        self.get_mut()
+            .start_send_envelope(Envelope::new(item, None), None)
     }
 
     // Note that on futures-rs bounded channels poll_flush has a problematic implementation.
@@ -206,30 +1230,21 @@ where
     // We compensate for the error swallowing by checking `is_closed`.
     //
     fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        match self.get_mut() {
-            Sender::Bounded { tx, .. } => {
-                if tx.is_closed() {
-                    Poll::Ready(Err(ErrorKind::Closed.into()))
-                } else {
-                    Poll::Ready(Ok(()))
-                }
-            }
-
-            Sender::Unbounded { tx, .. } => {
-                if tx.is_closed() {
-                    Poll::Ready(Err(ErrorKind::Closed.into()))
-                } else {
-                    Poll::Ready(Ok(()))
-                }
-            }
+        if self.tx.is_closed() {
+            Poll::Ready(Err(ErrorKind::Closed.into()))
+        } else {
+            Poll::Ready(Ok(()))
         }
     }
 
     fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        match self.get_mut() {
-            Sender::Bounded { tx, .. } => Pin::new(tx).poll_close(cx).map_err(Into::into),
-            Sender::Unbounded { tx, .. } => Pin::new(tx).poll_close(cx).map_err(Into::into),
-        }
+        let this = self.get_mut();
+
+        let res = ready!(this.tx.poll_close(cx));
+
+        this.control.signal();
+
+        Poll::Ready(res)
     }
 }
 
@@ -237,14 +1252,244 @@ where
 //
 mod tests {
     use super::*;
+    use crate::{Observable, Pharos};
+    use static_assertions::assert_impl_all;
+
+    // Many combinators from futures need these.
+    //
+    assert_impl_all!(Events<usize>: Unpin, FusedStream, Send, Sync);
+
+    // Only terminated once the stream has ended, not as soon as it's closed.
+    //
+    #[test]
+    //
+    fn fused() {
+        let (mut events, mut tx) = Events::<usize>::new(ObserverId(0), ObserveConfig::default());
+
+        block_on(tx.send(1)).expect("send");
+        events.close();
+
+        assert!(!events.is_terminated());
+        assert_eq!(block_on(events.next()), Some(1));
+        assert!(!events.is_terminated());
+        assert_eq!(block_on(events.next()), None);
+        assert!(events.is_terminated());
+        assert_eq!(block_on(events.next()), None);
+    }
+
+    // Drive a subscription without StreamExt.
+    //
+    #[test]
+    //
+    fn poll_event() {
+        let (mut events, mut tx) = Events::<usize>::new(ObserverId(0), ObserveConfig::default());
+
+        block_on(tx.send(1)).expect("send");
+        block_on(tx.send(2)).expect("send");
+
+        block_on(poll_fn(|cx| {
+            assert_eq!(Pin::new(&mut events).poll_event(cx), Poll::Ready(Some(1)));
+            assert_eq!(Pin::new(&mut events).poll_close(cx), Poll::Ready(()));
+            assert!(events.is_closed());
+            assert_eq!(Pin::new(&mut events).poll_event(cx), Poll::Ready(Some(2)));
+            assert_eq!(Pin::new(&mut events).poll_event(cx), Poll::Ready(None));
+
+            ().into()
+        }));
+    }
+
+    // After stop_receiving the producer skips us, but queued events can still be read.
+    //
+    #[test]
+    //
+    fn stop_receiving() {
+        let mut ph = Pharos::default();
+        let mut events = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            ph.send(2).await.expect("send");
+
+            events.stop_receiving();
+
+            ph.send(3).await.expect("send");
+            assert_eq!(0, ph.num_observers());
+
+            assert_eq!(Some(1), events.next().await);
+            assert_eq!(Some(2), events.next().await);
+            assert_eq!(None, events.next().await);
+        });
+    }
+
+    // The capacity comes from the channel, an unbounded channel has none.
+    //
+    #[test]
+    //
+    fn capacity() {
+        let mut ph = Pharos::<usize>::default();
+
+        let bounded = ph.observe(Channel::Bounded(16).into()).expect("observe");
+        let ring = ph.observe(Channel::RingBuffer(8).into()).expect("observe");
+        let unbounded = ph.observe(Channel::Unbounded.into()).expect("observe");
+
+        assert_eq!(Some(16), bounded.capacity());
+        assert_eq!(Some(8), ring.capacity());
+        assert_eq!(None, unbounded.capacity());
+    }
+
+    // Once low priority events fill the channel, high priority ones still fit in the reserved slots.
+    //
+    #[test]
+    //
+    fn reserved_slots() {
+        let mut ph = Pharos::default();
+
+        let options = ObserveConfig::from(Channel::Bounded(2)).reserve(1, |evt| *evt >= 100);
+        let mut events = ph.observe(options).expect("observe");
+
+        assert_eq!(1, ph.broadcast(1).expect("broadcast").delivered);
+        assert_eq!(1, ph.broadcast(2).expect("broadcast").delivered);
+
+        let err = ph.broadcast(3).unwrap_err();
+        assert_eq!(ErrorKind::SendError, err.kind());
+
+        assert_eq!(1, ph.broadcast(100).expect("broadcast").delivered);
+        assert!(ph.broadcast(101).is_err());
+
+        block_on(async {
+            assert_eq!(Some(1), events.next().await);
+            assert_eq!(Some(2), events.next().await);
+            assert_eq!(Some(100), events.next().await);
+
+            // Taking events makes room for the producer again.
+            //
+            ph.send(4).await.expect("send");
+            assert_eq!(Some(4), events.next().await);
+        });
+    }
+
+    // Count all events up to the end of the stream.
+    //
+    #[test]
+    //
+    fn count() {
+        let (events, mut tx) = Events::<usize>::new(ObserverId(0), ObserveConfig::default());
+
+        block_on(async {
+            for i in 1..=4 {
+                tx.send(i).await.expect("send");
+            }
+
+            drop(tx);
+
+            assert_eq!(4, events.count().await);
+        });
+    }
+
+    // Everything sent before the pharos closes comes back in order.
+    //
+    #[test]
+    //
+    fn collect_until_close() {
+        let mut ph = Pharos::default();
+        let events = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            for i in 1..=4 {
+                ph.send(i).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![1, 2, 3, 4], events.collect_until_close().await);
+        });
+    }
+
+    // The event of the only subscription that gets one wins, after which all are closed.
+    //
+    #[test]
+    //
+    fn race_first() {
+        let mut ph = Pharos::default();
+
+        let subscriptions = [10, 1, 10]
+            .iter()
+            .map(|&n| {
+                ph.observe(ObserveConfig::default().filter_boxed(move |evt| *evt == n))
+                    .expect("observe")
+            })
+            .collect();
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+
+            assert_eq!(Some(1), Events::race_first(subscriptions).await);
+            assert_eq!(0, ph.num_observers());
+
+            // Without events, it ends once all subscriptions end.
+            //
+            let subscriptions = vec![
+                ph.observe(ObserveConfig::default()).expect("observe"),
+                ph.observe(ObserveConfig::default()).expect("observe"),
+            ];
+
+            ph.close().await.expect("close");
+
+            assert_eq!(None, Events::race_first(subscriptions).await);
+        });
+    }
+
+    // All events end up in the sink, which gets closed when the subscription ends.
+    //
+    #[test]
+    //
+    fn forward_to() {
+        let mut ph = Pharos::default();
+        let events = ph.observe(ObserveConfig::default()).expect("observe");
+        let (tx, rx) = mpsc::unbounded();
+
+        block_on(async {
+            for i in 1..=3 {
+                ph.send(i).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            events.forward_to(tx).await.expect("forward");
+
+            assert_eq!(vec![1, 2, 3], rx.collect::<Vec<_>>().await);
+        });
+    }
+
+    // When the sink fails, the subscription is gone and the pharos drops the observer.
+    //
+    #[test]
+    //
+    fn forward_to_error() {
+        let mut ph = Pharos::default();
+        let events = ph.observe(ObserveConfig::default()).expect("observe");
+        let (tx, rx) = mpsc::unbounded();
+
+        drop(rx);
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+
+            assert!(events.forward_to(tx).await.is_err());
+
+            ph.send(2).await.expect("send");
+            assert_eq!(0, ph.num_observers());
+        });
+    }
 
     #[test]
     //
     fn debug() {
-        let e = Events::<bool>::new(ObserveConfig::default());
+        let e = Events::<bool>::new(ObserverId(0), ObserveConfig::default());
 
         assert_eq!(
-            "Events { rx: pharos::events::Receiver::<bool>::Unbounded(_) }",
+            "Events { id: ObserverId(0), rx: pharos::events::Receiver::<bool>::Unbounded(_), closed: false, \
+             terminated: false, next_seq: 0, last_gap: None, capacity: None, control: pharos::events::Control { closed: false, paused: false } }",
             &format!("{:?}", e.0)
         );
     }
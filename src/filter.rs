@@ -57,6 +57,61 @@ impl<Event> Filter<Event>
 where
     Event: Clone + 'static + Sync + Send,
 {
+    /// Only accept every `n`th event, to downsample a busy stream. Every observer using this filter
+    /// counts for itself, starting with the events offered to it after it subscribed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    //
+    pub fn sample(n: usize) -> Self {
+        assert!(n > 0, "Filter::sample: n must be at least 1");
+
+        let mut count = 0;
+
+        Self::Closure(Box::new(move |_| {
+            count += 1;
+
+            if count == n {
+                count = 0;
+                true
+            } else {
+                false
+            }
+        }))
+    }
+
+    /// Only accept events of one enum variant, the one `variant` is the discriminant of. This saves writing
+    /// a closure that matches on the variant. Get the discriminant from any value of the variant you want:
+    ///
+    /// ```
+    /// use pharos::*;
+    /// use std::mem::discriminant;
+    ///
+    /// #[derive(Clone)]
+    /// //
+    /// enum Steps { Progress(u8), Done }
+    ///
+    /// let mut pharos = Pharos::<Steps>::default();
+    ///
+    /// // The value of the data in the variant doesn't matter.
+    /// //
+    /// let progress = pharos.observe( Filter::discriminant( discriminant( &Steps::Progress(0) ) ).into() );
+    /// ```
+    //
+    pub fn discriminant(variant: Discriminant<Event>) -> Self {
+        Self::Closure(Box::new(move |evt| mem::discriminant(evt) == variant))
+    }
+
+    /// A copy of a [Filter::Pointer]. Closures can't be cloned, so this is `None` for a [Filter::Closure].
+    //
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        match self {
+            Self::Pointer(f) => Some(Self::Pointer(*f)),
+            Self::Closure(_) => None,
+        }
+    }
+
     /// Invoke the predicate.
     //
     pub(crate) fn call(&mut self, evt: &Event) -> bool {
@@ -67,6 +122,90 @@ where
     }
 }
 
+/// A filter that many observers can use, for predicates that are expensive to evaluate, like a regex.
+/// When an event goes out, the predicate runs once and all observers with a clone of this filter
+/// get the same answer. Use it with [ObserveConfig::shared_filter](crate::ObserveConfig::shared_filter).
+///
+/// ```
+/// use pharos::*;
+///
+/// let mut pharos = Pharos::<String>::default();
+/// let errors     = SharedFilter::new( |evt: &String| evt.contains( "error" ) );
+///
+/// let a = pharos.observe( errors.clone().into() );
+/// let b = pharos.observe( errors        .into() );
+/// ```
+//
+pub struct SharedFilter<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    predicate: Arc<dyn Fn(&Event) -> bool + Sync + Send>,
+}
+
+impl<Event> SharedFilter<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    /// Create a shared filter. Clones of it count as the same filter.
+    //
+    pub fn new(predicate: impl Fn(&Event) -> bool + Sync + Send + 'static) -> Self {
+        Self {
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    /// Tells clones of this filter apart from other shared filters. Only unique as long as a clone is alive.
+    //
+    pub(crate) fn key(&self) -> usize {
+        Arc::as_ptr(&self.predicate) as *const () as usize
+    }
+
+    /// A [Filter::Closure] that calls the shared predicate.
+    //
+    pub(crate) fn to_filter(&self) -> Filter<Event> {
+        let predicate = self.predicate.clone();
+
+        Filter::Closure(Box::new(move |evt| predicate(evt)))
+    }
+}
+
+impl<Event> Clone for SharedFilter<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn clone(&self) -> Self {
+        Self {
+            predicate: self.predicate.clone(),
+        }
+    }
+}
+
+impl<Event> fmt::Debug for SharedFilter<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::SharedFilter<{}>", type_name::<Event>())
+    }
+}
+
+// The results of the shared filters that ran on the event that is going out, by their key.
+//
+#[derive(Default)]
+//
+pub(crate) struct FilterCache {
+    results: HashMap<usize, bool>,
+}
+
+impl FilterCache {
+    /// The result of the shared filter with this key, calling `filter` if it didn't run yet.
+    //
+    pub(crate) fn get_or_call(&mut self, key: usize, filter: impl FnOnce() -> bool) -> bool {
+        *self.results.entry(key).or_insert_with(filter)
+    }
+}
+
 impl<Event> fmt::Debug for Filter<Event>
 where
     Event: Clone + 'static + Sync + Send,
@@ -83,6 +222,7 @@ where
 //
 mod tests {
     use super::*;
+    use crate::{import::block_on, Observable, ObserveConfig, Pharos};
 
     #[test]
     //
@@ -93,4 +233,101 @@ mod tests {
         assert_eq!("pharos::Filter<bool>::Pointer(_)", &format!("{:?}", f));
         assert_eq!("pharos::Filter<bool>::Closure(_)", &format!("{:?}", g));
     }
+
+    #[derive(Clone, Debug, PartialEq)]
+    //
+    enum Steps {
+        Start,
+        Progress(u8),
+        Done,
+    }
+
+    // Only the chosen variant gets through, whatever its data.
+    //
+    #[test]
+    //
+    fn discriminant() {
+        let mut ph = Pharos::default();
+        let filter = Filter::discriminant(mem::discriminant(&Steps::Progress(0)));
+        let events = ph.observe(filter.into()).expect("observe");
+
+        block_on(async {
+            for evt in [
+                Steps::Start,
+                Steps::Progress(1),
+                Steps::Progress(2),
+                Steps::Done,
+            ] {
+                ph.send(evt).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            assert_eq!(
+                vec![Steps::Progress(1), Steps::Progress(2)],
+                events.collect::<Vec<_>>().await
+            );
+        });
+    }
+
+    // Only every third event gets through.
+    //
+    #[test]
+    //
+    fn sample() {
+        let mut ph = Pharos::default();
+        let events = ph.observe(Filter::sample(3).into()).expect("observe");
+
+        block_on(async {
+            for i in 1..=9 {
+                ph.send(i).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![3, 6, 9], events.collect::<Vec<_>>().await);
+        });
+    }
+
+    // Observers sharing a filter get the result of a single evaluation per event.
+    //
+    #[test]
+    //
+    fn shared() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+
+        let big = SharedFilter::new(move |evt: &usize| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            *evt > 2
+        });
+
+        let mut ph = Pharos::default();
+
+        let a = ph.observe(big.clone().into()).expect("observe");
+        let b = ph
+            .observe(ObserveConfig::default().shared_filter(big.clone()))
+            .expect("observe");
+        let c = ph
+            .observe(ObserveConfig::from(big).try_clone().expect("try_clone"))
+            .expect("observe");
+        let all = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            for i in 1..=4 {
+                ph.send(i).await.expect("send");
+            }
+
+            assert_eq!(4, ph.broadcast(5).expect("broadcast").delivered);
+
+            ph.close().await.expect("close");
+
+            assert_eq!(5, calls.load(Ordering::SeqCst));
+
+            assert_eq!(vec![3, 4, 5], a.collect::<Vec<_>>().await);
+            assert_eq!(vec![3, 4, 5], b.collect::<Vec<_>>().await);
+            assert_eq!(vec![3, 4, 5], c.collect::<Vec<_>>().await);
+            assert_eq!(vec![1, 2, 3, 4, 5], all.collect::<Vec<_>>().await);
+        });
+    }
 }
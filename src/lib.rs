@@ -7,7 +7,7 @@
 #![doc(html_root_url = "https://docs.rs/pharos")]
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
-#![allow(clippy::suspicious_else_formatting)]
+#![allow(clippy::suspicious_else_formatting, clippy::empty_docs)]
 #![warn(
     missing_debug_implementations,
     missing_docs,
@@ -22,38 +22,108 @@
     variant_size_differences
 )]
 
+mod adapters;
+mod batching;
+#[cfg(feature = "chaos")]
+mod chaos;
+mod circuit_breaker;
 mod error;
 mod events;
 mod filter;
+mod local_pharos;
+mod membership;
 mod observable;
+mod observable_fn;
+mod observer_id;
+mod observer_key;
 mod pharos;
+mod pharos_builder;
+mod queue;
+mod reconnecting;
+mod ring;
+mod sharded_pharos;
+mod spawner;
+mod split;
+mod sub_pharos;
+mod tiered;
+mod timer;
+mod wake_batch;
+
+#[cfg(feature = "futures-timer")]
+//
+pub use timer::FuturesTimer;
+
+#[cfg(feature = "serde")]
+//
+pub use adapters::Deserialized;
 
 pub use {
-    self::pharos::Pharos,
+    self::pharos::{BroadcastReport, OverLimitBehavior, Pharos, SendOutcome},
+    adapters::{
+        BoxedEvents, CombineLatest, EventsReader, Flatten, Heartbeat, Heartbeats, Inspect,
+        MapWhile, MergedEvents, ReadyChunks, ResultEvents, Sampled, Scan, TakeUntil, Throttle,
+        ThrottlePolicy,
+    },
+    batching::Batching,
+    circuit_breaker::CircuitBreaker,
     error::{Error, ErrorKind},
-    events::Events,
-    filter::Filter,
-    observable::{Channel, Observable, ObserveConfig},
+    events::{Events, SubscriptionGuard},
+    filter::{Filter, SharedFilter},
+    local_pharos::{LocalEvents, LocalPharos},
+    membership::Membership,
+    observable::{Channel, DynObservable, Observable, ObserveConfig},
+    observable_fn::{observable_fn, ObservableFn},
+    observer_id::ObserverId,
+    pharos_builder::PharosBuilder,
+    reconnecting::{reconnecting_observe, Reconnecting, RetryPolicy},
+    sharded_pharos::ShardedPharos,
+    spawner::{FanOutReport, Spawner},
+    split::{Producer, Registrar},
+    sub_pharos::SubPharos,
+    timer::Timer,
 };
 
 mod import {
     pub(crate) use {
-        futures::{ready, Sink, Stream},
-        futures_channel::mpsc::{
-            self, Receiver as FutReceiver, SendError as FutSendError, Sender as FutSender,
-            UnboundedReceiver as FutUnboundedReceiver, UnboundedSender as FutUnboundedSender,
+        futures::{
+            future::poll_fn,
+            pin_mut, ready,
+            stream::FusedStream,
+            task::{noop_waker_ref, AtomicWaker},
+            Sink, SinkExt, Stream, StreamExt,
+        },
+        futures_channel::{
+            mpsc::{
+                self, Receiver as FutReceiver, SendError as FutSendError, Sender as FutSender,
+                UnboundedReceiver as FutUnboundedReceiver, UnboundedSender as FutUnboundedSender,
+            },
+            oneshot,
+        },
+        futures_io::AsyncRead,
+        std::future::Future,
+        std::panic::{catch_unwind, AssertUnwindSafe},
+        std::{
+            any::{type_name, Any},
+            borrow::Cow,
+            collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+            error::Error as ErrorTrait,
+            fmt,
+            hash::{Hash, Hasher},
+            io,
+            mem::{self, Discriminant},
         },
-        std::{any::type_name, error::Error as ErrorTrait, fmt, ops::Deref},
         std::{
             pin::Pin,
-            task::{Context, Poll},
+            sync::{
+                atomic::{AtomicBool, AtomicUsize, Ordering},
+                Arc, Mutex, MutexGuard, PoisonError, Weak,
+            },
+            task::{Context, Poll, Waker},
+            time::{Duration, Instant},
         },
     };
 
     #[cfg(test)]
     //
-    pub(crate) use {
-        assert_matches::assert_matches,
-        futures::{executor::block_on, future::poll_fn, SinkExt},
-    };
+    pub(crate) use {assert_matches::assert_matches, futures::executor::block_on};
 }
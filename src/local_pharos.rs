@@ -0,0 +1,393 @@
+use crate::{
+    import::*,
+    ring::{ring, RingReceiver, RingSender},
+    Channel, Error, ErrorKind,
+};
+
+/// A [Pharos](crate::Pharos) for single threaded use. Events only need to be `Clone + 'static`, so types
+/// that are not `Send` or `Sync`, like those holding an `Rc`, can be broadcast. Neither the pharos nor the
+/// [LocalEvents] streams it hands out can be sent to other threads when the events aren't `Send`.
+///
+/// This supports the core of the pharos API: observing with a [Channel] and an optional filter, sending events
+/// through the [Sink] impl and closing. It does not implement [Observable](crate::Observable), as that requires
+/// events to be `Send + Sync`.
+//
+pub struct LocalPharos<Event>
+where
+    Event: Clone + 'static,
+{
+    observers: Vec<Option<LocalSender<Event>>>,
+    free_slots: Vec<usize>,
+    closed: bool,
+}
+
+// Observers never get moved or pinned.
+//
+impl<Event> Unpin for LocalPharos<Event> where Event: Clone + 'static {}
+
+impl<Event> LocalPharos<Event>
+where
+    Event: Clone + 'static,
+{
+    /// Create a new local pharos. See [Pharos::new](crate::Pharos::new).
+    //
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            observers: Vec::with_capacity(capacity),
+            free_slots: Vec::with_capacity(capacity),
+            closed: false,
+        }
+    }
+
+    /// Observe this pharos with the given channel. See [Observable::observe](crate::Observable::observe).
+    //
+    pub fn observe(&mut self, channel: Channel) -> Result<LocalEvents<Event>, Error> {
+        self.new_observer(channel, None)
+    }
+
+    /// Observe this pharos, only receiving the events for which `filter` returns `true`.
+    //
+    pub fn observe_filtered(
+        &mut self,
+        channel: Channel,
+        filter: impl FnMut(&Event) -> bool + 'static,
+    ) -> Result<LocalEvents<Event>, Error> {
+        self.new_observer(channel, Some(Box::new(filter)))
+    }
+
+    /// Returns the number of observers that are still listening, removing the others.
+    /// See [Pharos::num_observers](crate::Pharos::num_observers).
+    //
+    pub fn num_observers(&mut self) -> usize {
+        let mut count = 0;
+
+        for (i, opt) in self.observers.iter_mut().enumerate() {
+            if let Some(observer) = opt {
+                if !observer.tx.is_closed() {
+                    count += 1;
+                } else {
+                    self.free_slots.push(i);
+                    *opt = None
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Whether this pharos has been closed.
+    //
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    fn new_observer(
+        &mut self,
+        channel: Channel,
+        filter: Option<LocalFilter<Event>>,
+    ) -> Result<LocalEvents<Event>, Error> {
+        if self.closed {
+            return Err(ErrorKind::Closed.into());
+        }
+
+        let (tx, rx) = match channel {
+            Channel::Bounded(0) | Channel::RingBuffer(0) => {
+                return Err(ErrorKind::MinChannelSizeOne.into())
+            }
+
+            Channel::Bounded(size) => {
+                let (tx, rx) = mpsc::channel(size - 1);
+                (LocalTx::Bounded(tx), LocalRx::Bounded(rx))
+            }
+
+            Channel::Unbounded => {
+                let (tx, rx) = mpsc::unbounded();
+                (LocalTx::Unbounded(tx), LocalRx::Unbounded(rx))
+            }
+
+            Channel::RingBuffer(size) => {
+                let (tx, rx) = ring(size);
+                (LocalTx::Ring(tx), LocalRx::Ring(rx))
+            }
+
+            _ => unreachable!(),
+        };
+
+        let sender = LocalSender { tx, filter };
+
+        if let Some(i) = self.free_slots.pop() {
+            self.observers[i] = Some(sender);
+        } else {
+            self.observers.push(Some(sender));
+        }
+
+        Ok(LocalEvents { rx })
+    }
+
+    // Remove the observer in slot `i`.
+    //
+    fn remove(observers: &mut [Option<LocalSender<Event>>], free_slots: &mut Vec<usize>, i: usize) {
+        free_slots.push(i);
+        observers[i] = None;
+    }
+}
+
+impl<Event> Default for LocalPharos<Event>
+where
+    Event: Clone + 'static,
+{
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl<Event> fmt::Debug for LocalPharos<Event>
+where
+    Event: Clone + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::LocalPharos<{}>", type_name::<Event>())
+    }
+}
+
+impl<Event> Sink<Event> for LocalPharos<Event>
+where
+    Event: Clone + 'static,
+{
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+
+        if this.closed {
+            return Err(ErrorKind::Closed.into()).into();
+        }
+
+        for i in 0..this.observers.len() {
+            if let Some(obs) = &mut this.observers[i] {
+                if ready!(obs.tx.poll_ready(cx)).is_err() {
+                    Self::remove(&mut this.observers, &mut this.free_slots, i);
+                }
+            }
+        }
+
+        Ok(()).into()
+    }
+
+    fn start_send(self: Pin<&mut Self>, evt: Event) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+
+        if this.closed {
+            return Err(ErrorKind::Closed.into());
+        }
+
+        for i in 0..this.observers.len() {
+            let obs = match &mut this.observers[i] {
+                Some(obs) => obs,
+                None => continue,
+            };
+
+            let interested = match &mut obs.filter {
+                Some(filter) => filter(&evt),
+                None => true,
+            };
+
+            if interested && obs.tx.start_send(evt.clone()).is_err() {
+                Self::remove(&mut this.observers, &mut this.free_slots, i);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Channels don't need flushing, see the Sink impl of events::Sender.
+    //
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.closed {
+            return Err(ErrorKind::Closed.into()).into();
+        }
+
+        Ok(()).into()
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+
+        this.closed = true;
+
+        // Dropping the senders ends the streams of the observers.
+        //
+        this.observers.clear();
+        this.free_slots.clear();
+
+        Ok(()).into()
+    }
+}
+
+// Like Filter::Closure, but the closure doesn't have to be Send or Sync.
+//
+type LocalFilter<Event> = Box<dyn FnMut(&Event) -> bool>;
+
+struct LocalSender<Event> {
+    tx: LocalTx<Event>,
+    filter: Option<LocalFilter<Event>>,
+}
+
+enum LocalTx<Event> {
+    Bounded(FutSender<Event>),
+    Unbounded(FutUnboundedSender<Event>),
+    Ring(RingSender<Event>),
+}
+
+impl<Event> LocalTx<Event> {
+    fn is_closed(&self) -> bool {
+        match self {
+            LocalTx::Bounded(tx) => tx.is_closed(),
+            LocalTx::Unbounded(tx) => tx.is_closed(),
+            LocalTx::Ring(tx) => tx.is_closed(),
+        }
+    }
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), FutSendError>> {
+        match self {
+            LocalTx::Bounded(tx) => tx.poll_ready(cx),
+            LocalTx::Unbounded(tx) => tx.poll_ready(cx),
+            LocalTx::Ring(_) => Ok(()).into(),
+        }
+    }
+
+    fn start_send(&mut self, evt: Event) -> Result<(), ()> {
+        match self {
+            LocalTx::Bounded(tx) => tx.start_send(evt).map_err(|_| ()),
+            LocalTx::Unbounded(tx) => tx.start_send(evt).map_err(|_| ()),
+            LocalTx::Ring(tx) => tx.send(evt).map(|_| ()).map_err(|_| ()),
+        }
+    }
+}
+
+/// The stream of events from a [LocalPharos].
+//
+pub struct LocalEvents<Event> {
+    rx: LocalRx<Event>,
+}
+
+enum LocalRx<Event> {
+    Bounded(FutReceiver<Event>),
+    Unbounded(FutUnboundedReceiver<Event>),
+    Ring(RingReceiver<Event>),
+}
+
+impl<Event> LocalEvents<Event> {
+    /// Disconnect from the pharos. Events that are still queued can still be read.
+    //
+    pub fn close(&mut self) {
+        match &mut self.rx {
+            LocalRx::Bounded(rx) => rx.close(),
+            LocalRx::Unbounded(rx) => rx.close(),
+            LocalRx::Ring(rx) => rx.close(),
+        }
+    }
+}
+
+impl<Event> Stream for LocalEvents<Event> {
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match &mut self.get_mut().rx {
+            LocalRx::Bounded(rx) => Pin::new(rx).poll_next(cx),
+            LocalRx::Unbounded(rx) => Pin::new(rx).poll_next(cx),
+            LocalRx::Ring(rx) => Pin::new(rx).poll_next(cx),
+        }
+    }
+}
+
+impl<Event> fmt::Debug for LocalEvents<Event> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::LocalEvents<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+    use std::rc::Rc;
+
+    // Broadcast events that are neither Send nor Sync.
+    //
+    #[test]
+    //
+    fn not_send() {
+        let mut ph = LocalPharos::default();
+
+        let all = ph.observe(Channel::Unbounded).expect("observe");
+        let big = ph
+            .observe_filtered(Channel::Bounded(5), |n: &Rc<usize>| **n > 1)
+            .expect("observe");
+
+        assert_eq!(ph.num_observers(), 2);
+
+        block_on(async {
+            ph.send(Rc::new(1)).await.expect("send");
+            ph.send(Rc::new(2)).await.expect("send");
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![Rc::new(1), Rc::new(2)], all.collect::<Vec<_>>().await);
+            assert_eq!(vec![Rc::new(2)], big.collect::<Vec<_>>().await);
+        });
+
+        assert_eq!(
+            ErrorKind::Closed,
+            ph.observe(Channel::Unbounded).unwrap_err().kind()
+        );
+    }
+
+    // The slot of an observer that went away is given to the next one.
+    //
+    #[test]
+    //
+    fn slot_reuse() {
+        let mut ph = LocalPharos::<Rc<usize>>::default();
+
+        let first = ph.observe(Channel::Unbounded).expect("observe");
+        let _second = ph.observe(Channel::RingBuffer(2)).expect("observe");
+
+        drop(first);
+
+        assert_eq!(1, ph.num_observers());
+        assert_eq!(vec![0], ph.free_slots);
+
+        let _third = ph.observe(Channel::Bounded(1)).expect("observe");
+
+        assert_eq!(2, ph.observers.len());
+        assert!(ph.free_slots.is_empty());
+        assert_eq!(2, ph.num_observers());
+    }
+
+    // poll_ready removes observers that went away, and doesn't wait for them.
+    //
+    #[test]
+    //
+    fn poll_ready_drops_disconnected() {
+        let mut ph = LocalPharos::<Rc<usize>>::default();
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        let gone = ph.observe(Channel::Bounded(1)).expect("observe");
+        let mut live = ph.observe(Channel::Bounded(1)).expect("observe");
+
+        drop(gone);
+
+        assert_matches!(Pin::new(&mut ph).poll_ready(cx), Poll::Ready(Ok(())));
+        assert!(ph.observers[0].is_none());
+        assert_eq!(vec![0], ph.free_slots);
+
+        Pin::new(&mut ph)
+            .start_send(Rc::new(1))
+            .expect("start_send");
+
+        assert_eq!(
+            Poll::Ready(Some(Rc::new(1))),
+            Pin::new(&mut live).poll_next(cx)
+        );
+    }
+}
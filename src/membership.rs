@@ -0,0 +1,27 @@
+use crate::{import::*, ObserverId, Pharos};
+
+/// A change in the observers of a [Pharos]. See [Pharos::membership_events].
+//
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+//
+pub enum Membership {
+    /// An observer subscribed.
+    //
+    Joined(ObserverId),
+
+    /// An observer was removed, because it went away or failed. This is only announced once the pharos
+    /// notices, eg. when sending an event or calling [Pharos::num_observers].
+    //
+    Left(ObserverId),
+}
+
+// The pharos that broadcasts the membership changes of another pharos. It's shared with the
+// observers, since they announce that they left when they get dropped.
+//
+pub(crate) type MembershipHub = Arc<Mutex<Pharos<Membership>>>;
+
+pub(crate) fn announce(hub: &MembershipHub, change: Membership) {
+    hub.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .announce(change);
+}
@@ -1,4 +1,9 @@
-use crate::{Events, Filter};
+use crate::import::Duration;
+use crate::{observer_key::ObserverKey, Error, ErrorKind, Events, Filter, SharedFilter};
+use std::any::{type_name, Any};
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::Hash;
 
 /// Indicate that a type is observable. You can call [`observe`](Observable::observe) to get a
 /// stream of events.
@@ -92,11 +97,109 @@ where
     /// to filter events with a predicate.
     //
     fn observe(&mut self, options: ObserveConfig<Event>) -> Result<Events<Event>, Self::Error>;
+
+    /// Add `n` observers with the same options, eg. for a pool of workers consuming the same events.
+    /// Every observer gets its own independent subscription.
+    ///
+    /// # Errors
+    ///
+    /// When `n` is more than one, the options have to be copied, which is not possible with a
+    /// [Filter::Closure], an [overflow callback](ObserveConfig::overflow_to), a [transform](ObserveConfig::transform),
+    /// [metadata](ObserveConfig::metadata) or a [key](ObserveConfig::key). In that case
+    /// this fails with [ErrorKind::ConfigNotCloneable] before adding any observer. Errors from
+    /// [observe](Observable::observe) are passed on, dropping the observers added so far.
+    //
+    fn observe_many(
+        &mut self,
+        n: usize,
+        options: ObserveConfig<Event>,
+    ) -> Result<Vec<Events<Event>>, Self::Error>
+    where
+        Self::Error: From<Error>,
+    {
+        let mut observers = Vec::with_capacity(n);
+
+        if n == 0 {
+            return Ok(observers);
+        }
+
+        for _ in 1..n {
+            let copy = options
+                .try_clone()
+                .ok_or_else(|| Error::from(ErrorKind::ConfigNotCloneable))?;
+
+            observers.push(self.observe(copy)?);
+        }
+
+        observers.push(self.observe(options)?);
+
+        Ok(observers)
+    }
+
+    /// Observe events that carry serialized data, eg. messages coming off the network, and get them
+    /// parsed as JSON into `T`. Messages that don't parse come out as `Err` items, the stream keeps
+    /// going after them. See [Deserialized](crate::Deserialized).
+    ///
+    /// Requires the `serde` feature.
+    //
+    #[cfg(feature = "serde")]
+    //
+    fn observe_deserialized<T>(
+        &mut self,
+        options: ObserveConfig<Event>,
+    ) -> Result<crate::Deserialized<Event, T>, Self::Error>
+    where
+        Event: AsRef<[u8]>,
+        T: serde::de::DeserializeOwned,
+    {
+        self.observe(options).map(crate::Deserialized::new)
+    }
+}
+
+/// An object safe version of [Observable]. The error type is fixed to [pharos::Error](crate::Error), so
+/// different observable types can be stored together as `Box<dyn DynObservable<Event>>`.
+///
+/// This is implemented for every [Observable] whose error converts into [pharos::Error](crate::Error),
+/// which includes everything that forwards to a [Pharos](crate::Pharos).
+///
+/// ```
+/// use pharos::*;
+///
+/// let mut observables: Vec< Box<dyn DynObservable<usize>> > = vec!
+/// [
+///    Box::new( Pharos::default() ),
+///    Box::new( observable_fn( || Some(1) ) ),
+/// ];
+///
+/// for o in &mut observables
+/// {
+///    o.observe_dyn( ObserveConfig::default() ).expect( "observe" );
+/// }
+/// ```
+//
+pub trait DynObservable<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    /// Add an observer. See [Observable::observe].
+    //
+    fn observe_dyn(&mut self, options: ObserveConfig<Event>) -> Result<Events<Event>, Error>;
+}
+
+impl<T, Event> DynObservable<Event> for T
+where
+    T: Observable<Event> + ?Sized,
+    T::Error: Into<Error>,
+    Event: Clone + 'static + Sync + Send,
+{
+    fn observe_dyn(&mut self, options: ObserveConfig<Event>) -> Result<Events<Event>, Error> {
+        self.observe(options).map_err(Into::into)
+    }
 }
 
 /// Choose the type of channel that will be used for your event stream. Used in [ObserveConfig].
 //
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 //
 pub enum Channel {
     /// A channel with a limited message queue (the usize parameter). Creates back pressure when the buffer is full.
@@ -109,16 +212,44 @@ pub enum Channel {
     /// A channel with unbounded capacity. Note that this may lead to unbounded memory consumption if producers
     /// outpace consumers.
     //
+    #[default]
     Unbounded,
 
+    /// A channel that holds at most the given number of events. Instead of creating back pressure, the oldest
+    /// event is dropped when a new one arrives while it's full. Consumers that fall behind lose events, but
+    /// never slow down the producer or use unbounded memory.
+    ///
+    /// The minimum valid buffer size is 1.
+    //
+    RingBuffer(usize),
+
     /// This enum might grow in the future, thanks to this that won't be a breaking change.
     //
     __NonExhaustive__,
 }
 
-impl Default for Channel {
-    fn default() -> Self {
-        Channel::Unbounded
+impl Channel {
+    /// Create a [Channel::Bounded], verifying that `size` is valid. This way an invalid size is caught when
+    /// creating the channel rather than when calling [Observable::observe].
+    ///
+    /// Fails with [ErrorKind::MinChannelSizeOne] if `size` is zero.
+    //
+    pub fn try_bounded(size: usize) -> Result<Self, Error> {
+        if size == 0 {
+            return Err(ErrorKind::MinChannelSizeOne.into());
+        }
+
+        Ok(Channel::Bounded(size))
+    }
+}
+
+/// Create a bounded channel with the given size. See [Channel::try_bounded].
+//
+impl TryFrom<usize> for Channel {
+    type Error = Error;
+
+    fn try_from(size: usize) -> Result<Self, Self::Error> {
+        Self::try_bounded(size)
     }
 }
 
@@ -165,13 +296,58 @@ pub struct ObserveConfig<Event>
 where
     Event: Clone + 'static + Sync + Send,
 {
-    pub(crate) channel: Channel,
+    // `None` means the default channel of the observable, see PharosBuilder::channel.
+    //
+    pub(crate) channel: Option<Channel>,
     pub(crate) filter: Option<Filter<Event>>,
+    pub(crate) shared_filter: Option<SharedFilter<Event>>,
+    pub(crate) metadata: Option<Box<dyn Any + Send + Sync>>,
+    pub(crate) key: Option<ObserverKey>,
+    pub(crate) priority: i32,
+    pub(crate) reserved: usize,
+    pub(crate) is_high: Option<fn(&Event) -> bool>,
+    pub(crate) overflow: Option<Overflow<Event>>,
+    pub(crate) transform: Option<ObserverTransform<Event>>,
+    pub(crate) no_replay: bool,
+    pub(crate) max_errors: Option<(usize, Duration)>,
+}
+
+/// Where the events go that don't fit in the channel of an observer, see [ObserveConfig::overflow_to].
+//
+pub(crate) struct Overflow<Event>(Box<dyn FnMut(Event) + Send + Sync>);
+
+impl<Event> Overflow<Event> {
+    pub(crate) fn call(&mut self, evt: Event) {
+        (self.0)(evt)
+    }
+}
+
+impl<Event> fmt::Debug for Overflow<Event> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::Overflow<{}>", type_name::<Event>())
+    }
+}
+
+/// Changes the events for a single observer, see [ObserveConfig::transform].
+//
+pub(crate) struct ObserverTransform<Event>(Box<dyn FnMut(Event) -> Event + Send + Sync>);
+
+impl<Event> ObserverTransform<Event> {
+    pub(crate) fn call(&mut self, evt: Event) -> Event {
+        (self.0)(evt)
+    }
+}
+
+impl<Event> fmt::Debug for ObserverTransform<Event> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::ObserverTransform<{}>", type_name::<Event>())
+    }
 }
 
 /// Create a default configuration:
 /// - no filter
-/// - an unbounded channel
+/// - the default channel of the observable, which is unbounded unless changed with
+///   [PharosBuilder::channel](crate::PharosBuilder::channel)
 //
 impl<Event> Default for ObserveConfig<Event>
 where
@@ -179,8 +355,18 @@ where
 {
     fn default() -> Self {
         Self {
-            channel: Channel::default(),
+            channel: None,
             filter: None,
+            shared_filter: None,
+            metadata: None,
+            key: None,
+            priority: 0,
+            reserved: 0,
+            is_high: None,
+            overflow: None,
+            transform: None,
+            no_replay: false,
+            max_errors: None,
         }
     }
 }
@@ -192,7 +378,7 @@ where
     /// Choose which channel implementation to use for your event stream.
     //
     pub fn channel(mut self, channel: Channel) -> Self {
-        self.channel = channel;
+        self.channel = Some(channel);
         self
     }
 
@@ -209,6 +395,89 @@ where
         self
     }
 
+    /// Attach some data to the observer, like a tag to route events with or for diagnostics. It can be retrieved
+    /// with [Pharos::observer_metadata](crate::Pharos::observer_metadata).
+    //
+    pub fn metadata<M>(mut self, value: M) -> Self
+    where
+        M: Any + Send + Sync,
+    {
+        self.metadata = Some(Box::new(value));
+        self
+    }
+
+    /// Register the observer under a key, so it receives the events sent with
+    /// [Pharos::notify_keyed](crate::Pharos::notify_keyed) for that key. Events sent to all observers still arrive.
+    //
+    pub fn key<K>(mut self, key: K) -> Self
+    where
+        K: Hash + Eq + Any + Send + Sync,
+    {
+        self.key = Some(ObserverKey::new(key));
+        self
+    }
+
+    /// Set the priority of the observer for [Pharos::close_ordered](crate::Pharos::close_ordered), which
+    /// closes observers with a higher priority first. The default is `0`.
+    //
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Reserve `slots` extra places in a [Channel::Bounded] for events for which `is_high` returns true. Other
+    /// events can only fill the size of the channel, so a flood of them can't keep important events out. This
+    /// matters when sending doesn't wait for room, like with [Pharos::broadcast](crate::Pharos::broadcast):
+    /// events that don't fit are missed. Events are still received in the order they were sent.
+    ///
+    /// Other kinds of channel ignore this.
+    //
+    pub fn reserve(mut self, slots: usize, is_high: fn(&Event) -> bool) -> Self {
+        self.reserved = slots;
+        self.is_high = Some(is_high);
+        self
+    }
+
+    /// Hand the events that don't fit in a [Channel::Bounded] to `callback`, eg. to write them to disk,
+    /// instead of making the producer wait. For observers that can't lose events, but shouldn't hold up
+    /// the producer either. Events that fit in the channel are received as usual.
+    //
+    pub fn overflow_to(mut self, callback: impl FnMut(Event) + Send + Sync + 'static) -> Self {
+        self.overflow = Some(Overflow(Box::new(callback)));
+        self
+    }
+
+    /// Change every event before it goes into the channel of this observer, after the filter ran, eg. to give
+    /// it a redacted view of the events. Other observers are not affected. Unlike mapping the stream on the
+    /// consumer side, this keeps the [Events] type. To change the events for all observers, see
+    /// [Pharos::set_transform](crate::Pharos::set_transform).
+    //
+    pub fn transform(mut self, f: impl FnMut(Event) -> Event + Send + Sync + 'static) -> Self {
+        self.transform = Some(ObserverTransform(Box::new(f)));
+        self
+    }
+
+    /// Remove the observer once sending to it failed more than `n` times within `window`. Methods that don't
+    /// wait for room, like [Pharos::broadcast](crate::Pharos::broadcast), keep an observer whose channel is full,
+    /// since it might catch up. This stops them from trying forever with one that doesn't, even if some events
+    /// get through in between. By default there is no limit.
+    ///
+    /// The window is measured with the [Timer](crate::Timer) of the pharos. Without a timer, only failures in a
+    /// row count, and an event that gets through resets the count.
+    //
+    pub fn max_errors(mut self, n: usize, window: Duration) -> Self {
+        self.max_errors = Some((n, window));
+        self
+    }
+
+    /// Only get the events sent from now on, even if the pharos [replays](crate::Pharos::set_replay) its
+    /// history to new observers.
+    //
+    pub fn no_replay(mut self) -> Self {
+        self.no_replay = true;
+        self
+    }
+
     /// Filter your event stream with a predicate that is a closure that captures environment.
     /// It is preferred to use [filter](ObserveConfig::filter) if you can as this will box the closure.
     /// You can only set one filter per observable.
@@ -225,6 +494,70 @@ where
         self.filter = Some(Filter::Closure(Box::new(filter)));
         self
     }
+
+    /// Filter your event stream with a [SharedFilter]. Observers with a clone of the same shared filter get
+    /// the result of a single call per event. You can only set one filter per observable.
+    //
+    pub fn shared_filter(mut self, filter: SharedFilter<Event>) -> Self {
+        debug_assert!(
+            self.filter.is_none(),
+            "You can only set one filter on ObserveConfig"
+        );
+
+        self.filter = Some(filter.to_filter());
+        self.shared_filter = Some(filter);
+        self
+    }
+
+    /// The channel chosen with [ObserveConfig::channel]. `None` means the default channel of the observable
+    /// is used.
+    //
+    pub fn get_channel(&self) -> Option<&Channel> {
+        self.channel.as_ref()
+    }
+
+    /// A copy of these options with another channel, to use one config as a template for several observers.
+    /// `None` if the options can't be copied, because they hold a [Filter::Closure], an
+    /// [overflow callback](ObserveConfig::overflow_to), a [transform](ObserveConfig::transform),
+    /// [metadata](ObserveConfig::metadata) or a [key](ObserveConfig::key).
+    //
+    pub fn with_channel(&self, channel: Channel) -> Option<Self> {
+        self.try_clone().map(|copy| copy.channel(channel))
+    }
+
+    // A copy of these options. `None` if they hold something that can't be cloned: a closure, metadata
+    // or a key.
+    //
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        if self.metadata.is_some()
+            || self.key.is_some()
+            || self.overflow.is_some()
+            || self.transform.is_some()
+        {
+            return None;
+        }
+
+        let filter = match (&self.shared_filter, &self.filter) {
+            (Some(shared), _) => Some(shared.to_filter()),
+            (None, Some(filter)) => Some(filter.try_clone()?),
+            (None, None) => None,
+        };
+
+        Some(Self {
+            channel: self.channel,
+            filter,
+            shared_filter: self.shared_filter.clone(),
+            metadata: None,
+            key: None,
+            priority: self.priority,
+            reserved: self.reserved,
+            is_high: self.is_high,
+            overflow: None,
+            transform: None,
+            no_replay: self.no_replay,
+            max_errors: self.max_errors,
+        })
+    }
 }
 
 /// Create a [ObserveConfig] from a [Channel], getting default values for other options.
@@ -245,8 +578,162 @@ where
     Event: Clone + 'static + Sync + Send,
 {
     fn from(filter: Filter<Event>) -> Self {
-        let mut s = Self::default();
-        s.filter = Some(filter);
-        s
+        Self {
+            filter: Some(filter),
+            ..Self::default()
+        }
+    }
+}
+
+/// Create a [ObserveConfig] from a [SharedFilter], getting default values for other options.
+//
+impl<Event> From<SharedFilter<Event>> for ObserveConfig<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    fn from(filter: SharedFilter<Event>) -> Self {
+        Self::default().shared_filter(filter)
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+    use std::convert::TryFrom;
+
+    // An observable that shares it's pharos, so we can still send events once it's boxed.
+    //
+    struct Shared(Arc<Mutex<Pharos<usize>>>);
+
+    impl Observable<usize> for Shared {
+        type Error = Error;
+
+        fn observe(&mut self, options: ObserveConfig<usize>) -> Result<Events<usize>, Error> {
+            self.0.lock().expect("lock").observe(options)
+        }
+    }
+
+    // A bounded channel needs room for at least one event.
+    //
+    #[test]
+    //
+    fn try_bounded() {
+        let err = Channel::try_bounded(0).unwrap_err();
+
+        assert_eq!(ErrorKind::MinChannelSizeOne, err.kind());
+        assert_eq!(
+            Channel::Bounded(1),
+            Channel::try_bounded(1).expect("try_bounded")
+        );
+        assert_eq!(Channel::Bounded(3), Channel::try_from(3).expect("try_from"));
+    }
+
+    // Store different observables behind the object safe trait and observe each.
+    //
+    #[test]
+    //
+    fn dyn_observable() {
+        let shared = Arc::new(Mutex::new(Pharos::default()));
+
+        let mut observables: Vec<Box<dyn DynObservable<usize>>> = vec![
+            Box::new(Shared(shared.clone())),
+            Box::new(Pharos::default()),
+        ];
+
+        let mut streams: Vec<Events<usize>> = observables
+            .iter_mut()
+            .map(|o| o.observe_dyn(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        {
+            let mut ph = shared.lock().expect("lock");
+            block_on(ph.send(5)).expect("send");
+        }
+
+        drop(observables);
+        drop(shared);
+
+        block_on(async {
+            assert_eq!(Some(5), streams[0].next().await);
+            assert_eq!(None, streams[0].next().await);
+            assert_eq!(None, streams[1].next().await);
+        });
+    }
+
+    // Every observer from observe_many gets the events.
+    //
+    #[test]
+    //
+    fn observe_many() {
+        let mut ph = Pharos::default();
+
+        let workers = ph
+            .observe_many(3, Filter::Pointer(|evt| *evt > 1).into())
+            .expect("observe_many");
+
+        assert_eq!(3, workers.len());
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            ph.send(2).await.expect("send");
+            ph.close().await.expect("close");
+
+            for worker in workers {
+                assert_eq!(vec![2], worker.collect::<Vec<_>>().await);
+            }
+        });
+    }
+
+    // Closures can't be copied, so no observer is added.
+    //
+    #[test]
+    //
+    fn observe_many_closure() {
+        let mut ph = Pharos::<usize>::default();
+
+        let err = ph
+            .observe_many(2, ObserveConfig::default().filter_boxed(|_| true))
+            .unwrap_err();
+
+        assert_eq!(ErrorKind::ConfigNotCloneable, err.kind());
+        assert_eq!(0, ph.num_observers());
+
+        assert_eq!(
+            1,
+            ph.observe_many(1, ObserveConfig::default().metadata(5))
+                .expect("observe_many")
+                .len()
+        );
+    }
+
+    // A variant of a config gets the new channel and keeps the filter, the original is unchanged.
+    //
+    #[test]
+    //
+    fn with_channel() {
+        let mut ph = Pharos::default();
+
+        let base = ObserveConfig::from(Channel::Bounded(1)).filter(|evt| *evt > 1);
+        let variant = base.with_channel(Channel::Unbounded).expect("with_channel");
+
+        assert_eq!(Some(&Channel::Bounded(1)), base.get_channel());
+        assert_eq!(Some(&Channel::Unbounded), variant.get_channel());
+        assert_eq!(None, ObserveConfig::<usize>::default().get_channel());
+
+        let bounded = ph.observe(base).expect("observe");
+        let unbounded = ph.observe(variant).expect("observe");
+
+        for i in 1..=3 {
+            ph.broadcast(i).ok();
+        }
+
+        block_on(ph.close()).expect("close");
+
+        assert_eq!(vec![2], block_on(bounded.collect::<Vec<_>>()));
+        assert_eq!(vec![2, 3], block_on(unbounded.collect::<Vec<_>>()));
+
+        let closure = ObserveConfig::<usize>::default().filter_boxed(|_| true);
+        assert!(closure.with_channel(Channel::Unbounded).is_none());
     }
 }
@@ -0,0 +1,130 @@
+use crate::{import::*, Error, Events, Observable, ObserveConfig, Pharos};
+
+/// Make a closure that produces events observable. See [ObservableFn].
+///
+/// ```
+/// use pharos::*;
+/// use futures::{executor::block_on, stream::StreamExt};
+///
+/// let mut count = 0;
+///
+/// let mut numbers = observable_fn( move ||
+/// {
+///    count += 1;
+///    if count <= 3 { Some( count ) } else { None }
+/// });
+///
+/// let events = numbers.observe( ObserveConfig::default() ).expect( "observe" );
+///
+/// block_on( async
+/// {
+///    numbers.run().await.expect( "run" );
+///
+///    assert_eq!( vec![ 1, 2, 3 ], events.collect::<Vec<usize>>().await );
+/// });
+/// ```
+//
+pub fn observable_fn<Event, F>(producer: F) -> ObservableFn<Event, F>
+where
+    Event: 'static + Clone + Sync + Send,
+    F: FnMut() -> Option<Event>,
+{
+    ObservableFn {
+        pharos: Pharos::default(),
+        producer,
+    }
+}
+
+/// An [Observable] backed by a closure. Each call to the closure produces the next event, `None`
+/// meaning that there are no more events. Observers subscribe with [Observable::observe] and
+/// the closure gets driven by [ObservableFn::run].
+//
+pub struct ObservableFn<Event, F>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    pharos: Pharos<Event>,
+    producer: F,
+}
+
+impl<Event, F> ObservableFn<Event, F>
+where
+    Event: 'static + Clone + Sync + Send,
+    F: FnMut() -> Option<Event>,
+{
+    /// Call the closure until it returns `None`, sending every event to the observers. When done
+    /// the underlying [Pharos] is closed, so observers will see the end of their stream.
+    //
+    pub async fn run(mut self) -> Result<(), Error> {
+        while let Some(evt) = (self.producer)() {
+            self.pharos.send(evt).await?;
+        }
+
+        self.pharos.close().await
+    }
+}
+
+impl<Event, F> Observable<Event> for ObservableFn<Event, F>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    type Error = Error;
+
+    fn observe(&mut self, options: ObserveConfig<Event>) -> Result<Events<Event>, Self::Error> {
+        self.pharos.observe(options)
+    }
+}
+
+impl<Event, F> fmt::Debug for ObservableFn<Event, F>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::ObservableFn<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use super::*;
+    use crate::Filter;
+
+    #[test]
+    //
+    fn debug() {
+        let obs = observable_fn(|| Some(true));
+
+        assert_eq!("pharos::ObservableFn<bool>", &format!("{:?}", obs));
+    }
+
+    // The closure emits incrementing numbers until it returns None.
+    //
+    #[test]
+    //
+    fn incrementing() {
+        let mut count = 0;
+
+        let mut numbers = observable_fn(move || {
+            count += 1;
+
+            if count <= 5 {
+                Some(count)
+            } else {
+                None
+            }
+        });
+
+        let evts = numbers.observe(ObserveConfig::default()).expect("observe");
+        let odd = numbers
+            .observe(Filter::Pointer(|n| n % 2 == 1).into())
+            .expect("observe");
+
+        block_on(async {
+            numbers.run().await.expect("run");
+
+            assert_eq!(vec![1, 2, 3, 4, 5], evts.collect::<Vec<usize>>().await);
+            assert_eq!(vec![1, 3, 5], odd.collect::<Vec<usize>>().await);
+        });
+    }
+}
@@ -0,0 +1,6 @@
+/// Identifies an observer of a [Pharos](crate::Pharos). Every observer gets a new id, even if it
+/// reuses the storage slot of an observer that went away. Get it with [Events::id](crate::Events::id).
+//
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+//
+pub struct ObserverId(pub(crate) u64);
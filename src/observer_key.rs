@@ -0,0 +1,47 @@
+use crate::import::*;
+
+/// A key an observer was registered under with [ObserveConfig::key](crate::ObserveConfig::key), used to
+/// route events with [Pharos::notify_keyed](crate::Pharos::notify_keyed). The type of the key is erased, so
+/// we store its hash for looking up the group and the value itself to tell keys with the same hash apart.
+//
+pub(crate) struct ObserverKey {
+    hash: u64,
+    value: Box<dyn Any + Send + Sync>,
+}
+
+impl ObserverKey {
+    pub(crate) fn new<K>(key: K) -> Self
+    where
+        K: Hash + Eq + Any + Send + Sync,
+    {
+        Self {
+            hash: Self::hash_of(&key),
+            value: Box::new(key),
+        }
+    }
+
+    pub(crate) fn hash_of<K: Hash>(key: &K) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub(crate) fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    // Whether this is `key`. Keys of another type never match.
+    //
+    pub(crate) fn matches<K>(&self, key: &K) -> bool
+    where
+        K: Eq + Any,
+    {
+        self.value.downcast_ref::<K>() == Some(key)
+    }
+}
+
+impl fmt::Debug for ObserverKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::ObserverKey {{ hash: {} }}", self.hash)
+    }
+}
@@ -1,5 +1,15 @@
 use crate::{
-    events::Sender, import::*, Channel, Error, ErrorKind, Events, Observable, ObserveConfig,
+    batching::Batch,
+    circuit_breaker::Breaker,
+    events::{Deadline, Envelope, Sender},
+    filter::FilterCache,
+    import::*,
+    membership::{announce, Membership, MembershipHub},
+    observer_key::ObserverKey,
+    wake_batch::WakeBatch,
+    Batching, Channel, CircuitBreaker, Error, ErrorKind, Events, FanOutReport, Filter, Heartbeats,
+    Observable, ObserveConfig, ObserverId, PharosBuilder, Producer, Registrar, ResultEvents,
+    Sampled, Spawner, SubPharos, SubscriptionGuard, Timer,
 };
 
 /// The Pharos lighthouse. When you implement [Observable] on your type, you can forward
@@ -14,8 +24,8 @@ use crate::{
 /// ## Implementation.
 ///
 /// Currently just holds a `Vec<Option<Sender>>`. It will drop observers if the channel has
-/// returned an error, which means it is closed or disconnected. However, we currently don't
-/// compact the vector. Slots are reused for new observers, but the vector never shrinks.
+/// returned an error, which means it is closed or disconnected. Slots are reused for new observers, but the
+/// vector only shrinks when you turn on [auto compaction](Pharos::set_auto_compact).
 ///
 /// **Note**: we only detect that observers can be removed when [SinkExt::send](https://docs.rs/futures-preview/0.3.0-alpha.19/futures/sink/trait.SinkExt.html#method.send) or [Pharos::num_observers]
 /// is being called. Otherwise, we won't find out about disconnected observers and the vector of observers
@@ -27,6 +37,22 @@ use crate::{
 ///
 /// TODO: I will do some benchmarking and see if this can be improved, eg. by keeping a state which tracks which
 /// observers we still have to poll.
+///
+/// ## Waking observers.
+///
+/// When one task polls several observers, eg. a merged stream, an event that goes out to all of them wakes
+/// that task once, but only for observers with a [Channel::RingBuffer] or a [Channel::Bounded] with
+/// [reserved slots](crate::ObserveConfig::reserve). The other channels come from `futures_channel`, which
+/// wakes the receiver on every send, so with the default [Channel::Unbounded] the task is woken once for
+/// each of its observers. That costs spurious polls, no events are lost.
+///
+/// ## Concurrent producers.
+///
+/// Sending takes `&mut self`, so producers on several tasks or threads share a pharos behind a lock, like
+/// `Arc<futures::lock::Mutex<Pharos<_>>>`. Every event is handed to each observer as a whole while the lock
+/// is held, so observers never see a partial or mixed up event, and they get the events of one producer
+/// in the order that producer sent them. How the events of different producers interleave depends on who
+/// gets the lock first and is not specified.
 //
 pub struct Pharos<Event>
 where
@@ -38,6 +64,64 @@ where
     observers: Vec<Option<Sender<Event>>>,
     free_slots: Vec<usize>,
     state: State,
+
+    // The most recent events, oldest first. Holds at most `history_len` events.
+    //
+    history: VecDeque<Event>,
+    history_len: usize,
+    replay: bool,
+
+    // The most recent event, kept for new observers if cache_last is set.
+    //
+    cache_last: bool,
+    last: Option<Event>,
+
+    catch_filter_panics: bool,
+
+    breaker: Option<Breaker>,
+    dropped: u64,
+
+    timer: Option<Arc<dyn Timer>>,
+    max_observers: Option<usize>,
+    over_limit: OverLimitBehavior,
+    debug_max_storage: Option<usize>,
+    next_id: u64,
+
+    // The slots of observers registered with a key, by the hash of the key. Entries are not removed
+    // when observers go away, they get pruned in notify_keyed.
+    //
+    keys: HashMap<u64, Vec<usize>>,
+
+    membership: Option<MembershipHub>,
+    batch: Option<Batch<Event>>,
+
+    // The observers that were not ready the last time we polled them in poll_ready.
+    //
+    blocking: Vec<ObserverId>,
+    auto_compact: Option<f32>,
+
+    // The mutex is only there to make the closure Sync, we always have `&mut self` to get at it.
+    //
+    transform: Option<Mutex<Transform<Event>>>,
+
+    // Events this rejects don't go out to any observer.
+    //
+    filter: Option<Filter<Event>>,
+    default_channel: Channel,
+
+    // How long events stay fresh, and the deadline for the next event if given by send_with_deadline.
+    //
+    ttl: Option<Duration>,
+    deadline: Option<Instant>,
+
+    // Yield to the executor once after this many events, see set_yield_after.
+    //
+    yield_after: Option<usize>,
+    sent_since_yield: usize,
+
+    #[cfg(feature = "chaos")]
+    //
+    shuffle: Option<crate::chaos::Shuffle>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -47,6 +131,213 @@ enum State {
     Closed,
 }
 
+// We never hand out pinned references to our fields, so pinning a Pharos does not need to
+// depend on whether the events it stores are Unpin.
+//
+impl<Event> Unpin for Pharos<Event> where Event: 'static + Clone + Sync + Send {}
+
+/// The result of [Pharos::send_if_ready].
+//
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+//
+pub enum SendOutcome {
+    /// All observers were ready and the event was sent.
+    //
+    Sent,
+
+    /// This many observers have a full channel. The event was not sent.
+    //
+    WouldBlock(usize),
+}
+
+/// The result of [Pharos::broadcast].
+//
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+//
+pub struct BroadcastReport {
+    /// Observers that got the event.
+    //
+    pub delivered: usize,
+
+    /// Observers that got the event and now have more events queued than the soft limit they were given with
+    /// [Pharos::observe_with_backlog_limit]. They are falling behind, but still get events up to the size of
+    /// their channel.
+    //
+    pub lagging: Vec<ObserverId>,
+}
+
+/// What [Observable::observe] does once the pharos has as many observers as allowed by
+/// [Pharos::set_max_observers]. See [Pharos::set_over_limit_behavior].
+//
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+//
+pub enum OverLimitBehavior {
+    /// Fail with [ErrorKind::TooManyObservers].
+    //
+    #[default]
+    Error,
+
+    /// Return a stream that is already closed, it yields `None` right away.
+    //
+    Graceful,
+}
+
+// What happened when offering an event to an observer.
+//
+#[derive(Debug)]
+//
+enum Delivery {
+    Sent,
+
+    // Paused or not interested.
+    //
+    Skip,
+
+    // Closed, sending failed or user code for this observer panicked, with the error.
+    //
+    Remove(Error),
+
+    // Cloning the event panicked. The event should not go out to any more observers.
+    //
+    Abort,
+}
+
+// The slots notify_parallel took the observers out of. When dropped, the slots of observers that
+// didn't come back can be reused. Those observers announce that they left when their task drops them.
+//
+struct TakenSlots<'a, Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    pharos: &'a mut Pharos<Event>,
+    taken: Vec<usize>,
+}
+
+impl<Event> Drop for TakenSlots<'_, Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    fn drop(&mut self) {
+        for &i in &self.taken {
+            if self.pharos.observers[i].is_none() {
+                self.pharos.free_slots.push(i);
+            }
+        }
+
+        self.pharos.debug_assert_invariants();
+    }
+}
+
+// Changes every event before it goes out, see Pharos::set_transform.
+//
+type Transform<Event> = Box<dyn FnMut(Event) -> Event + Send>;
+
+// Receivers for the acknowledgements of the observers an event was sent to. They tell whether the
+// observer read the event or skipped it because it expired.
+//
+type Acks = Vec<(ObserverId, oneshot::Receiver<bool>)>;
+
+// Lock a pharos that is shared between tasks or threads. A panicking filter poisons the lock, but the pharos
+// itself stays consistent, so keep going.
+//
+pub(crate) fn lock<Event>(pharos: &Mutex<Pharos<Event>>) -> MutexGuard<'_, Pharos<Event>>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    pharos.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Offer an event to a single observer. If `acks` is given, the observer gets asked to acknowledge
+// reception of the event. If it's not read before `deadline`, the observer skips it. Waking the
+// observer is left to `batch`, so a task polling several observers is woken once per event. Shared
+// filters keep their result for this event in `cache`.
+//
+fn deliver<Event>(
+    obs: &mut Sender<Event>,
+    evt: Cow<'_, Event>,
+    catch_filter_panics: bool,
+    deadline: Option<&Deadline>,
+    acks: Option<&mut Acks>,
+    batch: &mut WakeBatch,
+    cache: &mut FilterCache,
+) -> Delivery
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    // if it's closed, let's remove it.
+    //
+    if obs.is_closed() {
+        return Delivery::Remove(ErrorKind::SendError.into());
+    }
+
+    if obs.is_paused() {
+        obs.skip_paused();
+        return Delivery::Skip;
+    }
+
+    // is it interested in this event. A filter that panics costs the observer
+    // its subscription if we catch panics.
+    //
+    let interested = if catch_filter_panics {
+        catch_unwind(AssertUnwindSafe(|| obs.filter_cached(&evt, cache)))
+    } else {
+        Ok(obs.filter_cached(&evt, cache))
+    };
+
+    match interested {
+        Ok(true) => {}
+        Ok(false) => return Delivery::Skip,
+        Err(_) => return Delivery::Remove(ErrorKind::ObserverPanicked.into()),
+    }
+
+    // clone a borrowed event now, so a panic in clone doesn't unwind through the pharos.
+    //
+    let evt: Cow<'_, Event> = match evt {
+        Cow::Borrowed(evt) => match catch_unwind(AssertUnwindSafe(|| evt.clone())) {
+            Ok(evt) => Cow::Owned(evt),
+            Err(_) => return Delivery::Abort,
+        },
+
+        owned => owned,
+    };
+
+    // a full observer with an overflow callback gets the event through the callback. If sending fails,
+    // remove it. An owned event is moved in without cloning.
+    //
+    let send = || {
+        let evt = match obs.try_overflow(evt) {
+            Ok(()) => return Ok(()),
+            Err(evt) => evt,
+        };
+
+        let ack = acks.map(|acks| {
+            let (tx, rx) = oneshot::channel();
+            acks.push((obs.id(), rx));
+            tx
+        });
+
+        let envelope = Envelope::new(evt.into_owned(), ack).with_deadline(deadline.cloned());
+
+        obs.start_send_envelope(envelope, Some(batch))
+    };
+
+    // the transform of the observer runs while sending, so it gets the same treatment as the filter.
+    //
+    let sent = if catch_filter_panics {
+        match catch_unwind(AssertUnwindSafe(send)) {
+            Ok(sent) => sent,
+            Err(_) => return Delivery::Remove(ErrorKind::ObserverPanicked.into()),
+        }
+    } else {
+        send()
+    };
+
+    match sent {
+        Ok(()) => Delivery::Sent,
+        Err(e) => Delivery::Remove(e),
+    }
+}
+
 impl<Event> fmt::Debug for Pharos<Event>
 where
     Event: 'static + Clone + Sync + Send,
@@ -65,13 +356,41 @@ where
     /// You can set the initial capacity of the vector of observers, if you know you will a lot of observers
     /// it will save allocations by setting this to a higher number.
     ///
-    /// For pharos 0.4.0 on x64 Linux: `std::mem::size_of::<Option<Sender<_>>>() == 56 bytes`.
+    /// For pharos 0.4.2 on x64 Linux: `std::mem::size_of::<Option<Sender<_>>>() == 296 bytes`.
     //
     pub fn new(capacity: usize) -> Self {
         Self {
             observers: Vec::with_capacity(capacity),
             free_slots: Vec::with_capacity(capacity),
             state: State::Ready,
+            history: VecDeque::new(),
+            history_len: 0,
+            replay: false,
+            cache_last: false,
+            last: None,
+            catch_filter_panics: false,
+            breaker: None,
+            dropped: 0,
+            timer: crate::timer::default_timer(),
+            max_observers: None,
+            over_limit: OverLimitBehavior::default(),
+            debug_max_storage: None,
+            next_id: 0,
+            keys: HashMap::new(),
+            membership: None,
+            batch: None,
+            blocking: Vec::new(),
+            auto_compact: None,
+            transform: None,
+            filter: None,
+            default_channel: Channel::default(),
+            ttl: None,
+            deadline: None,
+            yield_after: None,
+            sent_since_yield: 0,
+
+            #[cfg(feature = "chaos")]
+            shuffle: None,
         }
     }
 
@@ -82,6 +401,69 @@ where
         self.observers.len()
     }
 
+    /// The number of observers the storage can hold without reallocating. See [Pharos::shrink_to].
+    //
+    pub fn capacity(&self) -> usize {
+        self.observers.capacity()
+    }
+
+    /// Give memory back by reducing the capacity of the storage for observers to `min_capacity`, but never
+    /// below [Pharos::storage_len]. Slots of removed observers are kept for reuse, so this does not change
+    /// the storage length.
+    //
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.observers.shrink_to(min_capacity);
+        self.free_slots.shrink_to(min_capacity);
+    }
+
+    /// Compact the storage automatically once more than `ratio` of the slots are free, eg. `0.5` to compact
+    /// when over half of them are. This keeps memory bounded when many observers come and go. It's checked
+    /// after sending an event and in [Pharos::num_observers]. The remaining observers are moved to the front
+    /// of the storage and keep their [ObserverId], then the memory of the free slots is given back. Once
+    /// compacted, this only triggers again when the ratio is exceeded again.
+    ///
+    /// `None` turns it off, which is the default.
+    //
+    pub fn set_auto_compact(&mut self, ratio: Option<f32>) {
+        self.auto_compact = ratio;
+    }
+
+    // Compact the storage if more of it is free than allowed by auto_compact.
+    //
+    fn maybe_compact(&mut self) {
+        if let Some(ratio) = self.auto_compact {
+            let len = self.observers.len();
+
+            if len > 0 && self.free_slots.len() as f32 > ratio * len as f32 {
+                self.compact();
+            }
+        }
+
+        self.debug_assert_invariants();
+    }
+
+    // Move the observers to the front of the storage and drop the free slots. Observers change slot, so
+    // the index of keys gets rebuilt.
+    //
+    fn compact(&mut self) {
+        self.observers.retain(Option::is_some);
+        self.observers.shrink_to_fit();
+
+        self.free_slots.clear();
+        self.free_slots.shrink_to_fit();
+
+        self.keys.clear();
+
+        for i in 0..self.observers.len() {
+            let hash = self.observers[i]
+                .as_ref()
+                .and_then(Sender::key)
+                .map(ObserverKey::hash);
+
+            self.index_key(i, hash);
+        }
+    }
+
     /// Returns the number of actual observers that are still listening (have not closed or dropped the [Events]).
     /// This will loop and it will verify for each if they are closed, clearing them from the internal storage
     /// if they are closed. This is similar to what notify does, but without sending an event.
@@ -100,432 +482,4728 @@ where
             }
         }
 
+        self.maybe_compact();
+
         count
     }
-}
 
-/// Creates a new pharos, using 10 as the initial capacity of the vector used to store
-/// observers. If this number does really not fit your use case, call [Pharos::new].
-//
-impl<Event> Default for Pharos<Event>
-where
-    Event: 'static + Clone + Sync + Send,
-{
-    fn default() -> Self {
-        Self::new(10)
+    /// The number of observers, like the length of a collection of subscribers. This is cheap and doesn't
+    /// remove anything, so it's an approximation: observers that went away still count until they are
+    /// noticed, like when sending or calling [Pharos::num_observers], which gives the exact number.
+    //
+    pub fn len(&self) -> usize {
+        self.observers.len() - self.free_slots.len()
     }
-}
 
-impl<Event> Observable<Event> for Pharos<Event>
-where
-    Event: 'static + Clone + Sync + Send,
-{
-    type Error = Error;
+    /// Whether there are no observers, eg. to skip building an event nobody will receive. Like
+    /// [Pharos::len], observers that went away still count until they are noticed.
+    //
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
-    /// Will re-use slots from disconnected observers to avoid growing to much.
+    // Count the observers that are still listening without removing the others.
+    //
+    #[cfg(feature = "metrics")]
+    //
+    fn live_observers(&self) -> usize {
+        self.observers
+            .iter()
+            .flatten()
+            .filter(|obs| !obs.is_closed())
+            .count()
+    }
+
+    /// Export statistics about this pharos in the [Prometheus text format](https://prometheus.io/docs/instrumenting/exposition_formats/).
+    /// Every metric is labeled with the type name of the events.
     ///
-    /// TODO: provide API for the client to compact the pharos object after reducing the
-    ///       number of observers.
+    /// - `pharos_observers`: observers that are still listening
+    /// - `pharos_free_slots`: slots that will be reused for new observers
+    /// - `pharos_storage_len`: see [Pharos::storage_len]
+    /// - `pharos_dropped_events_total`: see [Pharos::dropped_events]
+    /// - `pharos_closed`: `1` if the pharos is closed, `0` otherwise
+    ///
+    /// This does not remove closed observers, so it can be called through a shared reference.
     //
-    fn observe(&mut self, options: ObserveConfig<Event>) -> Result<Events<Event>, Self::Error> {
-        if self.state == State::Closed {
-            return Err(ErrorKind::Closed.into());
+    #[cfg(feature = "metrics")]
+    //
+    pub fn export_prometheus(&self) -> String {
+        let label = type_name::<Event>()
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"");
+
+        let metrics = [
+            (
+                "pharos_observers",
+                "gauge",
+                "Observers that are still listening.",
+                self.live_observers() as u64,
+            ),
+            (
+                "pharos_free_slots",
+                "gauge",
+                "Storage slots that will be reused for new observers.",
+                self.free_slots.len() as u64,
+            ),
+            (
+                "pharos_storage_len",
+                "gauge",
+                "The size of the storage used for observers.",
+                self.observers.len() as u64,
+            ),
+            (
+                "pharos_dropped_events_total",
+                "counter",
+                "Events dropped because the circuit breaker was tripped.",
+                self.dropped,
+            ),
+            (
+                "pharos_closed",
+                "gauge",
+                "Whether the pharos is closed.",
+                self.is_closed() as u64,
+            ),
+        ];
+
+        let mut out = String::new();
+
+        for (name, kind, help, value) in metrics.iter() {
+            out.push_str(&format!(
+                "# HELP {name} {help}\n# TYPE {name} {kind}\n{name}{{event=\"{label}\"}} {value}\n",
+                name = name,
+                help = help,
+                kind = kind,
+                label = label,
+                value = value,
+            ));
         }
 
-        match options.channel {
-            Channel::Bounded(queue_size) => {
-                if queue_size < 1 {
-                    return Err(ErrorKind::MinChannelSizeOne.into());
-                }
-            }
+        out
+    }
+
+    /// Whether this pharos has been closed through [SinkExt::close](https://docs.rs/futures-preview/0.3.0-alpha.19/futures/sink/trait.SinkExt.html#method.close).
+    /// While closed, you can no longer send events or observe it.
+    //
+    pub fn is_closed(&self) -> bool {
+        self.state == State::Closed
+    }
+
+    /// The highest number of events that were waiting in the channel of each observer at the same time,
+    /// since it subscribed or since [Pharos::reset_peaks]. Useful for choosing the size of bounded channels.
+    ///
+    /// There is one entry per observer, in the order of their storage slots. Observers that have
+    /// been removed are not included.
+    //
+    pub fn peak_depths(&self) -> Vec<usize> {
+        self.observers
+            .iter()
+            .flatten()
+            .map(|obs| obs.peak_depth())
+            .collect()
+    }
 
-            _ => {}
+    /// Reset the peaks reported by [Pharos::peak_depths] to the number of events currently queued
+    /// for each observer.
+    //
+    pub fn reset_peaks(&mut self) {
+        for obs in self.observers.iter_mut().flatten() {
+            obs.reset_peak();
         }
+    }
 
-        let (events, sender) = Events::new(options);
+    /// Keep the last `len` events that were sent through this pharos, so they can be queried with
+    /// [Pharos::recent]. The default is `0`, which keeps no history. Reducing the length drops the
+    /// oldest events.
+    //
+    pub fn set_history_len(&mut self, len: usize) {
+        self.history_len = len;
 
-        // Try to reuse a free slot
-        //
-        if let Some(i) = self.free_slots.pop() {
-            self.observers[i] = Some(sender);
-        } else {
-            self.observers.push(Some(sender));
+        while self.history.len() > len {
+            self.history.pop_front();
         }
+    }
 
-        Ok(events)
+    /// Give new observers the events in the history first, see [Pharos::set_history_len], so they start out
+    /// with recent state. Events that don't fit in a bounded channel are left out, starting with the newest.
+    /// Observers can opt out with [ObserveConfig::no_replay]. Default is `false`.
+    ///
+    /// Observing and sending both take `&mut self`, so an event is either in the history when an observer
+    /// subscribes, or it goes out to that observer live, never both. The replay can be cut short though: with
+    /// a bounded channel that is too small for the history, the newest events of the history are lost. The
+    /// observer sees this in [Events::last_gap] once live events arrive.
+    //
+    pub fn set_replay(&mut self, replay: bool) {
+        self.replay = replay;
     }
-}
 
-// See the documentation on Channel for how poll functions work for the channels we use.
-//
-impl<Event> Sink<Event> for Pharos<Event>
-where
-    Event: Clone + 'static + Sync + Send,
-{
-    type Error = Error;
+    /// Keep the most recent event and give it to every new observer first, so it starts out with the
+    /// current value. This is lighter than [replaying](Pharos::set_replay) a history. The observer only gets
+    /// it if its filter accepts it, and not if it asks for [ObserveConfig::no_replay]. Turning this off
+    /// forgets the event. Default is `false`.
+    //
+    pub fn set_cache_last(&mut self, cache_last: bool) {
+        self.cache_last = cache_last;
 
-    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        if self.state == State::Closed {
-            return Err(ErrorKind::Closed.into()).into();
+        if !cache_last {
+            self.last = None;
         }
+    }
 
-        // As soon as any is not ready, we are not ready
-        //
-        for obs in self.get_mut().observers.iter_mut() {
-            if let Some(ref mut o) = obs {
-                let res = ready!(Pin::new(o).poll_ready(cx));
-
-                // Errors mean disconnected, so drop.
-                //
-                if res.is_err() {
-                    *obs = None;
-                }
+    // Queue the history in the channel of a new observer, as far as it fits. The events that don't fit
+    // are still offered, so they count as lost for the observer.
+    //
+    fn replay_to(&self, sender: &mut Sender<Event>) {
+        for evt in &self.history {
+            if sender.filter(evt) {
+                let _ = sender.start_send_envelope(Envelope::new(evt.clone(), None), None);
             }
         }
+    }
 
-        Ok(()).into()
+    /// Returns up to the last `n` events that were sent, oldest first. This only returns
+    /// events if a history was configured with [Pharos::set_history_len]. Events are recorded
+    /// whether or not any observer was interested in them.
+    //
+    pub fn recent(&self, n: usize) -> Vec<Event> {
+        let skip = self.history.len().saturating_sub(n);
+
+        self.history.iter().skip(skip).cloned().collect()
     }
 
-    fn start_send(self: Pin<&mut Self>, evt: Event) -> Result<(), Self::Error> {
-        if self.state == State::Closed {
-            return Err(ErrorKind::Closed.into());
-        }
+    /// The metadata that was attached to an observer with [ObserveConfig::metadata]. Returns `None`
+    /// if there is no observer with this id or it has no metadata. Use
+    /// [downcast_ref](https://doc.rust-lang.org/std/any/trait.Any.html#method.downcast_ref) to get at the value.
+    //
+    pub fn observer_metadata(&self, id: ObserverId) -> Option<&dyn Any> {
+        self.observers
+            .iter()
+            .flatten()
+            .find(|obs| obs.id() == id)
+            .and_then(|obs| obs.metadata())
+            .map(|m| -> &dyn Any { m })
+    }
 
-        let this = self.get_mut();
+    /// Limit the number of observers. Once `max` observers are listening, observing fails with
+    /// [ErrorKind::TooManyObservers] until some go away, see also [Pharos::set_over_limit_behavior].
+    /// By default there is no limit.
+    //
+    pub fn set_max_observers(&mut self, max: usize) {
+        self.max_observers = Some(max);
+    }
 
-        for (i, opt) in this.observers.iter_mut().enumerate() {
-            // if this spot in the vector has a sender
-            //
-            if let Some(obs) = opt {
-                // if it's closed, let's remove it.
-                //
-                if obs.is_closed() {
-                    this.free_slots.push(i);
+    /// Choose what [Observable::observe] does over the [limit](Pharos::set_max_observers). With
+    /// [OverLimitBehavior::Graceful] the caller gets a stream that is closed from the start, so code that
+    /// treats every subscription the same way just sees one without events. The default is
+    /// [OverLimitBehavior::Error]. Other ways to observe always fail over the limit.
+    //
+    pub fn set_over_limit_behavior(&mut self, behavior: OverLimitBehavior) {
+        self.over_limit = behavior;
+    }
 
-                    *opt = None;
-                }
-                // else if it is interested in this event
-                //
-                else if obs.filter(&evt) {
-                    // if sending fails, remove it
-                    //
-                    if Pin::new(obs).start_send(evt.clone()).is_err() {
-                        this.free_slots.push(i);
+    /// Panic in debug builds when [Pharos::storage_len] grows beyond `max`, to catch observers that leak
+    /// during development. Unlike [Pharos::set_max_observers], this never rejects observers and does nothing
+    /// in release builds.
+    //
+    pub fn debug_assert_max_observers(&mut self, max: usize) {
+        self.debug_max_storage = Some(max);
+    }
 
-                        *opt = None;
-                    }
-                }
+    /// Set the timer used for features that need to measure time, like [Pharos::send_timeout]. With the
+    /// `futures-timer` feature, the default is [FuturesTimer](crate::FuturesTimer), otherwise there is no timer
+    /// until you set one.
+    //
+    pub fn set_timer(&mut self, timer: Option<Arc<dyn Timer>>) {
+        self.timer = timer;
+    }
+
+    /// Like [SinkExt::send](https://docs.rs/futures-preview/0.3.0-alpha.19/futures/sink/trait.SinkExt.html#method.send),
+    /// but give up if the observers aren't ready to take the event within `timeout`. In that case, no observer
+    /// gets the event and this fails with [ErrorKind::Timeout].
+    ///
+    /// Fails with [ErrorKind::NoTimer] if no [Timer] is set.
+    //
+    pub async fn send_timeout(&mut self, evt: Event, timeout: Duration) -> Result<(), Error> {
+        let mut sleep = match &self.timer {
+            Some(timer) => timer.sleep(timeout),
+            None => return Err(ErrorKind::NoTimer.into()),
+        };
+
+        poll_fn(|cx| {
+            if let Poll::Ready(res) = Pin::new(&mut *self).poll_ready(cx) {
+                return Poll::Ready(res);
             }
-        }
 
-        Ok(())
+            sleep
+                .as_mut()
+                .poll(cx)
+                .map(|_| Err(ErrorKind::Timeout.into()))
+        })
+        .await?;
+
+        Pin::new(&mut *self).start_send(evt)?;
+
+        poll_fn(|cx| Pin::new(&mut *self).poll_flush(cx)).await
+    }
+
+    /// Give every event a deadline `ttl` after it goes out. Observers that haven't read an event by then
+    /// skip it, so slow observers don't act on stale events. Skipped events don't show up in
+    /// [Events::last_gap]. `None` turns this off, which is the default.
+    ///
+    /// This needs a [Timer], without one events never expire. With [batching](Pharos::set_batching), the
+    /// deadline starts when the batch is delivered.
+    //
+    pub fn set_ttl(&mut self, ttl: Option<Duration>) {
+        self.ttl = ttl;
+    }
+
+    /// After every `n` events, [poll_ready](Sink::poll_ready) returns `Poll::Pending` once and wakes
+    /// the task right away. On a single threaded executor, this lets consumers and other tasks run
+    /// while a producer sends in a tight loop, instead of waiting for it to finish. `None` turns this
+    /// off, which is the default.
+    ///
+    /// `Some(0)` is treated like `Some(1)`.
+    //
+    pub fn set_yield_after(&mut self, n: Option<usize>) {
+        self.yield_after = n;
+        self.sent_since_yield = 0;
+    }
+
+    /// Like [SinkExt::send](https://docs.rs/futures-preview/0.3.0-alpha.19/futures/sink/trait.SinkExt.html#method.send),
+    /// but observers skip the event if they haven't read it by `deadline`, which overrides the
+    /// [TTL](Pharos::set_ttl) for this event. The deadline is lost if the event gets
+    /// [batched](Pharos::set_batching).
+    ///
+    /// Fails with [ErrorKind::NoTimer] if no [Timer] is set.
+    //
+    pub async fn send_with_deadline(&mut self, evt: Event, deadline: Instant) -> Result<(), Error> {
+        if self.timer.is_none() {
+            return Err(ErrorKind::NoTimer.into());
+        }
+
+        poll_fn(|cx| Pin::new(&mut *self).poll_ready(cx)).await?;
+
+        self.deadline = Some(deadline);
+
+        let res = self.start_send_cow(Cow::Owned(evt));
+
+        self.deadline = None;
+        res?;
+
+        poll_fn(|cx| Pin::new(&mut *self).poll_flush(cx)).await
+    }
+
+    // The deadline for the event that is going out, from send_with_deadline or the TTL.
+    //
+    fn deadline(&mut self) -> Option<Deadline> {
+        let explicit = self.deadline.take();
+        let timer = self.timer.clone()?;
+        let at = explicit.or_else(|| self.ttl.map(|ttl| timer.now() + ttl))?;
+
+        Some(Deadline::new(at, timer))
+    }
+
+    /// Send an event only if that doesn't require waiting for any observer. Otherwise the event is not sent
+    /// and you learn how many observers would block, so you can decide to coalesce or drop events rather
+    /// than wait.
+    ///
+    /// Fails with [ErrorKind::Closed] if the pharos is closed.
+    //
+    pub fn send_if_ready(&mut self, evt: Event) -> Result<SendOutcome, Error> {
+        if self.is_closed() {
+            return Err(ErrorKind::Closed.into());
+        }
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+        let mut blocked = 0;
+
+        for (i, opt) in self.observers.iter_mut().enumerate() {
+            if let Some(obs) = opt {
+                match Pin::new(obs).poll_ready(cx) {
+                    Poll::Ready(Ok(())) => {}
+                    Poll::Pending => blocked += 1,
+
+                    // Errors mean disconnected, so drop.
+                    //
+                    Poll::Ready(Err(_)) => {
+                        self.free_slots.push(i);
+
+                        *opt = None;
+                    }
+                }
+            }
+        }
+
+        if blocked > 0 {
+            return Ok(SendOutcome::WouldBlock(blocked));
+        }
+
+        Pin::new(self).start_send(evt)?;
+
+        Ok(SendOutcome::Sent)
+    }
+
+    /// Send an event to all observers right away, without waiting for them to be ready. Observers with a full
+    /// channel miss the event, observers that went away are removed. Reports how many observers got the event,
+    /// and which of them are [over their backlog limit](Pharos::observe_with_backlog_limit). Events waiting in a
+    /// [batch](Pharos::set_batching) are not sent first.
+    ///
+    /// # Errors
+    ///
+    /// If sending to any observer failed, the event is still offered to all others, but this returns an
+    /// error of kind [ErrorKind::SendError]. [Error::observers] tells which observers failed and why, and
+    /// [Error::report] holds the report for the others. Fails with [ErrorKind::Closed] if the pharos is closed.
+    //
+    pub fn broadcast(&mut self, evt: Event) -> Result<BroadcastReport, Error> {
+        if self.is_closed() {
+            return Err(ErrorKind::Closed.into());
+        }
+
+        let (report, failed) = self.offer(evt);
+
+        if failed.is_empty() {
+            Ok(report)
+        } else {
+            Err(Error::failed_observers(failed).with_report(report))
+        }
+    }
+
+    /// Like [Sink::poll_ready], but ready as soon as at least one observer is ready, so a single full observer
+    /// doesn't hold up the others. Use it with [Pharos::start_send_any], or use [Pharos::send_any] to do both.
+    /// Also ready when there are no observers.
+    ///
+    /// Fails with [ErrorKind::Closed] if the pharos is closed.
+    //
+    pub fn poll_ready_any(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        if self.is_closed() {
+            return Err(ErrorKind::Closed.into()).into();
+        }
+
+        let mut live = false;
+        let mut ready = false;
+
+        for (i, opt) in self.observers.iter_mut().enumerate() {
+            if let Some(obs) = opt {
+                match Pin::new(obs).poll_ready(cx) {
+                    Poll::Ready(Ok(())) => ready = true,
+                    Poll::Pending => live = true,
+
+                    // Errors mean disconnected, so drop.
+                    //
+                    Poll::Ready(Err(_)) => {
+                        self.free_slots.push(i);
+
+                        *opt = None;
+                    }
+                }
+            }
+        }
+
+        if ready || !live {
+            Ok(()).into()
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Send an event to the observers that have room for it. Observers with a full channel miss the event,
+    /// but stay subscribed. Returns the number of observers that got the event. Call [Pharos::poll_ready_any]
+    /// first to make sure at least one observer has room.
+    ///
+    /// Fails with [ErrorKind::Closed] if the pharos is closed.
+    //
+    pub fn start_send_any(&mut self, evt: Event) -> Result<usize, Error> {
+        if self.is_closed() {
+            return Err(ErrorKind::Closed.into());
+        }
+
+        Ok(self.offer(evt).0.delivered)
+    }
+
+    /// Wait until at least one observer is ready and send the event to all observers that have room for it.
+    /// See [Pharos::poll_ready_any] and [Pharos::start_send_any].
+    //
+    pub async fn send_any(&mut self, evt: Event) -> Result<usize, Error> {
+        poll_fn(|cx| self.poll_ready_any(cx)).await?;
+
+        self.start_send_any(evt)
+    }
+
+    // Offer the event to all observers without waiting. Returns who got it and the observers that failed.
+    //
+    fn offer(&mut self, evt: Event) -> (BroadcastReport, Vec<(ObserverId, Error)>) {
+        let evt = match self.transform(Cow::Owned(evt)) {
+            Some(evt) => evt.into_owned(),
+            None => return (BroadcastReport::default(), Vec::new()),
+        };
+
+        let deadline = self.deadline();
+
+        self.record(&evt);
+
+        let now = self.now();
+        let mut report = BroadcastReport::default();
+        let mut failed = Vec::new();
+        let mut batch = WakeBatch::default();
+        let mut cache = FilterCache::default();
+
+        for (i, opt) in self.observers.iter_mut().enumerate() {
+            let obs = match opt {
+                Some(obs) => obs,
+                None => continue,
+            };
+
+            let id = obs.id();
+
+            let delivery = deliver(
+                obs,
+                Cow::Borrowed(&evt),
+                self.catch_filter_panics,
+                deadline.as_ref(),
+                None,
+                &mut batch,
+                &mut cache,
+            );
+
+            match delivery {
+                Delivery::Sent => {
+                    report.delivered += 1;
+
+                    if obs.is_lagging() {
+                        report.lagging.push(id);
+                    }
+                }
+
+                Delivery::Skip => {}
+
+                Delivery::Abort => {
+                    failed.push((id, ErrorKind::ClonePanicked.into()));
+                    break;
+                }
+
+                Delivery::Remove(err) => {
+                    // A full channel is no reason to drop the observer, unless it stays full.
+                    //
+                    if err.kind() == ErrorKind::ObserverPanicked
+                        || obs.is_closed()
+                        || obs.count_error(now)
+                    {
+                        self.free_slots.push(i);
+
+                        *opt = None;
+                    }
+
+                    failed.push((id, err));
+                }
+            }
+        }
+
+        self.debug_assert_invariants();
+
+        (report, failed)
+    }
+
+    /// Send an event and wait until every observer that is interested in it has taken it out of
+    /// its channel. Resolves to the number of observers that acknowledged the event. Observers that skipped
+    /// it because it [expired](Pharos::set_ttl) don't count, but they don't fail either.
+    ///
+    /// This first waits for all observers to be ready, like [SinkExt::send](https://docs.rs/futures-preview/0.3.0-alpha.19/futures/sink/trait.SinkExt.html#method.send).
+    ///
+    /// **Note**: this waits as long as it takes. A single observer that stops reading without dropping its
+    /// [Events] keeps the returned future pending forever. Use [Pharos::notify_acked_timeout] to bound the wait.
+    ///
+    /// # Errors
+    ///
+    /// If sending failed or observers disconnect before consuming the event, this still waits for all others,
+    /// but returns an error of kind [ErrorKind::SendError]. [Error::observers] tells which observers failed.
+    /// Fails with [ErrorKind::Closed] without sending anything if the pharos is closed.
+    //
+    pub async fn notify_acked(&mut self, evt: Event) -> Result<usize, Error> {
+        self.acked(evt, None).await
+    }
+
+    /// Like [Pharos::notify_acked], but give up after `timeout`. If the observers aren't ready by then, no
+    /// observer gets the event and this fails with [ErrorKind::Timeout]. Observers that got the event but didn't
+    /// take it out of their channel in time are listed in [Error::observers] with [ErrorKind::Timeout], next to
+    /// the ones that failed.
+    ///
+    /// Fails with [ErrorKind::NoTimer] if no [Timer] is set.
+    //
+    pub async fn notify_acked_timeout(
+        &mut self,
+        evt: Event,
+        timeout: Duration,
+    ) -> Result<usize, Error> {
+        let sleep = match &self.timer {
+            Some(timer) => timer.sleep(timeout),
+            None => return Err(ErrorKind::NoTimer.into()),
+        };
+
+        self.acked(evt, Some(sleep)).await
+    }
+
+    // What notify_acked and notify_acked_timeout do. Without `sleep`, this waits as long as it takes.
+    //
+    async fn acked(
+        &mut self,
+        evt: Event,
+        sleep: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    ) -> Result<usize, Error> {
+        let mut sleep = sleep;
+        let mut timed_out = false;
+
+        // Ready once the time is up. The sleep is not polled again after it fired.
+        //
+        let mut expired = |cx: &mut Context<'_>| {
+            if !timed_out {
+                timed_out = match &mut sleep {
+                    Some(sleep) => sleep.as_mut().poll(cx).is_ready(),
+                    None => false,
+                };
+            }
+
+            timed_out
+        };
+
+        poll_fn(|cx| {
+            if let Poll::Ready(res) = Pin::new(&mut *self).poll_ready(cx) {
+                return Poll::Ready(res);
+            }
+
+            if expired(cx) {
+                return Poll::Ready(Err(ErrorKind::Timeout.into()));
+            }
+
+            Poll::Pending
+        })
+        .await?;
+
+        let mut acks = Vec::new();
+
+        let mut failed = self.fan_out(Cow::Owned(evt), Some(&mut acks))?;
+
+        let mut count = 0;
+
+        for (id, mut ack) in acks {
+            let res = poll_fn(|cx| {
+                if let Poll::Ready(res) = Pin::new(&mut ack).poll(cx) {
+                    return Poll::Ready(res.map_err(|_| Error::from(ErrorKind::SendError)));
+                }
+
+                if expired(cx) {
+                    return Poll::Ready(Err(ErrorKind::Timeout.into()));
+                }
+
+                Poll::Pending
+            })
+            .await;
+
+            match res {
+                Ok(true) => count += 1,
+                Ok(false) => {}
+                Err(e) => failed.push((id, e)),
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(count)
+        } else {
+            Err(Error::failed_observers(failed))
+        }
+    }
+
+    /// Send an event only to the observers that were registered under `key` with [ObserveConfig::key].
+    /// Waits until all of those observers are ready, but not for any others. Their filters still apply.
+    ///
+    /// Keyed events bypass the history and the circuit breaker. Fails with [ErrorKind::Closed] if the
+    /// pharos is closed.
+    //
+    pub async fn notify_keyed<K>(&mut self, key: K, evt: Event) -> Result<(), Error>
+    where
+        K: Hash + Eq + Any + Send + Sync,
+    {
+        if self.is_closed() {
+            return Err(ErrorKind::Closed.into());
+        }
+
+        let evt = match self.transform(Cow::Owned(evt)) {
+            Some(evt) => evt.into_owned(),
+            None => return Ok(()),
+        };
+
+        let deadline = self.deadline();
+        let mut batch = WakeBatch::default();
+        let mut cache = FilterCache::default();
+        let hash = ObserverKey::hash_of(&key);
+        let observers = &self.observers;
+        let key_of = |i: usize| observers[i].as_ref().and_then(Sender::key);
+
+        // Forget slots that have been emptied or reused by an observer with another key.
+        // Different keys can have the same hash, so check the key itself too.
+        //
+        let slots = match self.keys.get_mut(&hash) {
+            Some(slots) => {
+                slots.retain(|&i| key_of(i).map(|k| k.hash() == hash).unwrap_or(false));
+
+                slots
+                    .iter()
+                    .copied()
+                    .filter(|&i| key_of(i).map(|k| k.matches(&key)).unwrap_or(false))
+                    .collect::<Vec<_>>()
+            }
+
+            None => return Ok(()),
+        };
+
+        poll_fn(|cx| {
+            for &i in &slots {
+                if let Some(obs) = &mut self.observers[i] {
+                    if ready!(Pin::new(obs).poll_ready(cx)).is_err() {
+                        self.free_slots.push(i);
+                        self.observers[i] = None;
+                    }
+                }
+            }
+
+            Poll::Ready(())
+        })
+        .await;
+
+        for i in slots {
+            if let Some(obs) = &mut self.observers[i] {
+                let delivery = deliver(
+                    obs,
+                    Cow::Borrowed(&evt),
+                    self.catch_filter_panics,
+                    deadline.as_ref(),
+                    None,
+                    &mut batch,
+                    &mut cache,
+                );
+
+                match delivery {
+                    Delivery::Remove(_) => {
+                        self.free_slots.push(i);
+                        self.observers[i] = None;
+                    }
+
+                    Delivery::Abort => return Err(ErrorKind::ClonePanicked.into()),
+                    Delivery::Sent | Delivery::Skip => {}
+                }
+            }
+        }
+
+        self.debug_assert_invariants();
+
+        Ok(())
+    }
+
+    // Deliver an event to all interested observers. If `acks` is given, every observer
+    // gets asked to acknowledge reception of the event.
+    //
+    // The last observer we visit gets the event itself if it's owned, the others get a clone. That
+    // way we never clone an owned event when there is only one observer.
+    //
+    // If cloning the event panics, the remaining observers don't get it and this fails with
+    // ErrorKind::ClonePanicked.
+    //
+    fn fan_out(
+        &mut self,
+        evt: Cow<'_, Event>,
+        mut acks: Option<&mut Acks>,
+    ) -> Result<Vec<(ObserverId, Error)>, Error> {
+        let evt = match self.transform(evt) {
+            Some(evt) => evt,
+            None => return Ok(Vec::new()),
+        };
+
+        let deadline = self.deadline();
+
+        self.record(&evt);
+
+        let mut failed = Vec::new();
+        let mut batch = WakeBatch::default();
+        let mut cache = FilterCache::default();
+
+        #[cfg(feature = "chaos")]
+        //
+        if let Some(shuffle) = &mut self.shuffle {
+            let mut order: Vec<usize> = (0..self.observers.len()).collect();
+            shuffle.shuffle(&mut order);
+
+            let observers = &self.observers;
+
+            if let Some(pos) = order.iter().rposition(|&i| observers[i].is_some()) {
+                let last = order.remove(pos);
+
+                for i in order {
+                    failed.extend(self.deliver_to(
+                        i,
+                        Cow::Borrowed(&*evt),
+                        deadline.as_ref(),
+                        acks.as_deref_mut(),
+                        &mut batch,
+                        &mut cache,
+                    )?);
+                }
+
+                failed.extend(self.deliver_to(
+                    last,
+                    evt,
+                    deadline.as_ref(),
+                    acks,
+                    &mut batch,
+                    &mut cache,
+                )?);
+            }
+
+            return Ok(failed);
+        }
+
+        if let Some(last) = self.observers.iter().rposition(Option::is_some) {
+            for i in 0..last {
+                failed.extend(self.deliver_to(
+                    i,
+                    Cow::Borrowed(&*evt),
+                    deadline.as_ref(),
+                    acks.as_deref_mut(),
+                    &mut batch,
+                    &mut cache,
+                )?);
+            }
+
+            failed.extend(self.deliver_to(
+                last,
+                evt,
+                deadline.as_ref(),
+                acks,
+                &mut batch,
+                &mut cache,
+            )?);
+        }
+
+        Ok(failed)
+    }
+
+    /// Like [SinkExt::send](https://docs.rs/futures-preview/0.3.0-alpha.19/futures/sink/trait.SinkExt.html#method.send),
+    /// but the event can be borrowed. Every observer gets a clone of a borrowed event. An owned event is
+    /// moved into the channel of the last observer, so it's cloned one time less than there are observers.
+    /// This saves a clone of large events when you have them by value, while still letting you send
+    /// events you want to keep.
+    //
+    pub async fn send_cow(&mut self, evt: Cow<'_, Event>) -> Result<(), Error> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_ready(cx)).await?;
+
+        self.start_send_cow(evt)?;
+
+        poll_fn(|cx| Pin::new(&mut *self).poll_flush(cx)).await
+    }
+
+    // What Sink::start_send does, for an event that might be borrowed.
+    //
+    fn start_send_cow(&mut self, evt: Cow<'_, Event>) -> Result<(), Error> {
+        if self.state == State::Closed {
+            return Err(ErrorKind::Closed.into());
+        }
+
+        let now = self.now();
+
+        if self.yield_after.is_some() {
+            self.sent_since_yield += 1;
+        }
+
+        if let Some(batch) = &mut self.batch {
+            batch.push(evt.into_owned(), now);
+
+            return Ok(());
+        }
+
+        if let Some(breaker) = &mut self.breaker {
+            if breaker.is_tripped() {
+                breaker.on_drop();
+                self.dropped += 1;
+
+                return Ok(());
+            }
+        }
+
+        let res = self.fan_out(evt, None).map(|_| ());
+        self.maybe_compact();
+
+        res
+    }
+
+    // Deliver an event to the observer in slot `i`, if any. Returns the error if sending failed. Fails
+    // with ErrorKind::ClonePanicked if the event couldn't be cloned for this observer.
+    //
+    fn deliver_to(
+        &mut self,
+        i: usize,
+        evt: Cow<'_, Event>,
+        deadline: Option<&Deadline>,
+        acks: Option<&mut Acks>,
+        batch: &mut WakeBatch,
+        cache: &mut FilterCache,
+    ) -> Result<Option<(ObserverId, Error)>, Error> {
+        // if this spot in the vector has a sender
+        //
+        let obs = match &mut self.observers[i] {
+            Some(obs) => obs,
+            None => return Ok(None),
+        };
+
+        let id = obs.id();
+
+        match deliver(
+            obs,
+            evt,
+            self.catch_filter_panics,
+            deadline,
+            acks,
+            batch,
+            cache,
+        ) {
+            Delivery::Remove(err) => {
+                self.free_slots.push(i);
+
+                self.observers[i] = None;
+
+                Ok(Some((id, err)))
+            }
+
+            Delivery::Abort => Err(ErrorKind::ClonePanicked.into()),
+            Delivery::Sent | Delivery::Skip => Ok(None),
+        }
+    }
+
+    /// Visit the observers in a random order every time an event is sent, instead of in the order they
+    /// subscribed, to shake out consumers that depend on that order. The same `seed` always gives the same
+    /// sequence of orders. `None` turns shuffling off again. Only available with the `chaos` feature.
+    //
+    #[cfg(feature = "chaos")]
+    //
+    pub fn set_shuffle_seed(&mut self, seed: Option<u64>) {
+        self.shuffle = seed.map(crate::chaos::Shuffle::new);
+    }
+
+    /// Take every event that is waiting in the channels of `observers` without blocking, tagged with the
+    /// id of the observer. The pharos only holds the sending side of the channels, so you pass in the
+    /// subscriptions. Events come out per observer, in the order of `observers`. Meant for asserting
+    /// on what everyone received in tests. Only available with the `testing` feature.
+    //
+    #[cfg(feature = "testing")]
+    //
+    pub fn drain_to_vec(observers: &mut [Events<Event>]) -> Vec<(ObserverId, Event)> {
+        let cx = &mut Context::from_waker(noop_waker_ref());
+        let mut drained = Vec::new();
+
+        for events in observers {
+            let id = events.id();
+
+            while let Poll::Ready(Some(evt)) = Pin::new(&mut *events).poll_next(cx) {
+                drained.push((id, evt));
+            }
+        }
+
+        drained
+    }
+
+    /// Panic if the bookkeeping of observer slots is inconsistent: every free slot must be empty and listed
+    /// only once, live observers must have distinct ids and keys must point into the storage. Meant for
+    /// stress tests. Only available with the `testing` feature.
+    //
+    #[cfg(feature = "testing")]
+    //
+    pub fn assert_invariants(&self) {
+        self.check_invariants();
+    }
+
+    // Check the bookkeeping of observer slots after changing it, in debug builds.
+    //
+    fn debug_assert_invariants(&self) {
+        #[cfg(debug_assertions)]
+        //
+        self.check_invariants();
+    }
+
+    #[cfg(any(debug_assertions, feature = "testing"))]
+    //
+    fn check_invariants(&self) {
+        let len = self.observers.len();
+        let mut free = vec![false; len];
+
+        for &i in &self.free_slots {
+            assert!(
+                i < len,
+                "Pharos: free slot {} is out of bounds ({})",
+                i,
+                len
+            );
+            assert!(
+                self.observers[i].is_none(),
+                "Pharos: free slot {} holds an observer",
+                i
+            );
+            assert!(
+                !free[i],
+                "Pharos: slot {} is listed as free more than once",
+                i
+            );
+
+            free[i] = true;
+        }
+
+        let mut ids: Vec<ObserverId> = self.observers.iter().flatten().map(Sender::id).collect();
+        let live = ids.len();
+
+        ids.sort();
+        ids.dedup();
+
+        assert_eq!(live, ids.len(), "Pharos: several observers share an id");
+
+        for &i in self.keys.values().flatten() {
+            assert!(
+                i < len,
+                "Pharos: keyed slot {} is out of bounds ({})",
+                i,
+                len
+            );
+        }
+    }
+
+    // Add an event to the history if we keep one.
+    //
+    fn record(&mut self, evt: &Event) {
+        if self.cache_last {
+            self.last = Some(evt.clone());
+        }
+
+        if self.history_len > 0 {
+            if self.history.len() == self.history_len {
+                self.history.pop_front();
+            }
+
+            self.history.push_back(evt.clone());
+        }
+    }
+
+    /// Like [SinkExt::send](https://docs.rs/futures-preview/0.3.0-alpha.19/futures/sink/trait.SinkExt.html#method.send),
+    /// but split the observers over up to `shards` tasks spawned on `spawner`, which deliver the event in parallel.
+    /// This only pays off when there are many observers and filters are expensive.
+    ///
+    /// Resolves once all tasks are done, with a report of what happened to each observer. If the executor drops
+    /// a task before it finishes, or a filter panics in it, the observers it held are removed. The same goes for
+    /// all observers if the returned future is dropped before it resolves.
+    ///
+    /// If cloning the event panics, the tasks stop handing it out and this fails with [ErrorKind::ClonePanicked].
+    /// The observers that didn't get the event yet are kept.
+    //
+    pub async fn notify_parallel(
+        &mut self,
+        evt: Event,
+        spawner: &dyn Spawner,
+        shards: usize,
+    ) -> Result<FanOutReport, Error> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_ready(cx)).await?;
+
+        let evt = match self.transform(Cow::Owned(evt)) {
+            Some(evt) => evt.into_owned(),
+
+            None => {
+                return Ok(FanOutReport {
+                    skipped: self.observers.iter().flatten().count(),
+                    ..FanOutReport::default()
+                })
+            }
+        };
+
+        let deadline = self.deadline();
+
+        self.record(&evt);
+
+        let observers: Vec<(usize, Sender<Event>)> = self
+            .observers
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, opt)| opt.take().map(|obs| (i, obs)))
+            .collect();
+
+        let taken: Vec<usize> = observers.iter().map(|(i, _)| *i).collect();
+        let chunk_size = taken.len().div_ceil(shards.max(1)).max(1);
+        let mut tasks = Vec::new();
+        let mut observers = observers.into_iter().peekable();
+
+        let abort = Arc::new(AtomicBool::new(false));
+
+        while observers.peek().is_some() {
+            let mut chunk: Vec<_> = observers.by_ref().take(chunk_size).collect();
+            let (tx, rx) = oneshot::channel();
+            let evt = evt.clone();
+            let deadline = deadline.clone();
+            let abort = abort.clone();
+            let catch = self.catch_filter_panics;
+
+            spawner.spawn(Box::pin(async move {
+                let mut report = FanOutReport::default();
+                let mut batch = WakeBatch::default();
+                let mut cache = FilterCache::default();
+
+                chunk.retain_mut(|(_, obs)| {
+                    if abort.load(Ordering::Acquire) {
+                        return true;
+                    }
+
+                    match deliver(
+                        obs,
+                        Cow::Borrowed(&evt),
+                        catch,
+                        deadline.as_ref(),
+                        None,
+                        &mut batch,
+                        &mut cache,
+                    ) {
+                        Delivery::Sent => {
+                            report.delivered += 1;
+                            true
+                        }
+
+                        Delivery::Skip => {
+                            report.skipped += 1;
+                            true
+                        }
+
+                        Delivery::Abort => {
+                            abort.store(true, Ordering::Release);
+                            true
+                        }
+
+                        Delivery::Remove(_) => false,
+                    }
+                });
+
+                let _ = tx.send((chunk, report));
+            }));
+
+            tasks.push(rx);
+        }
+
+        let mut report = FanOutReport::default();
+        let mut returned = 0;
+        let removed = taken.len();
+
+        // From here on, the slots are freed even if we get dropped while waiting for the tasks.
+        //
+        let slots = TakenSlots {
+            pharos: self,
+            taken,
+        };
+
+        for task in tasks {
+            if let Ok((chunk, part)) = task.await {
+                report.delivered += part.delivered;
+                report.skipped += part.skipped;
+                returned += chunk.len();
+
+                for (i, obs) in chunk {
+                    slots.pharos.observers[i] = Some(obs);
+                }
+            }
+        }
+
+        drop(slots);
+
+        if abort.load(Ordering::Acquire) {
+            return Err(ErrorKind::ClonePanicked.into());
+        }
+
+        report.removed = removed - returned;
+
+        Ok(report)
+    }
+
+    /// When set, filters are run with [catch_unwind](std::panic::catch_unwind). An observer whose
+    /// filter panics is removed, and the event still goes out to all other observers. The same goes for
+    /// the [transform](ObserveConfig::transform) of an observer. Methods that report which observers failed,
+    /// like [Pharos::broadcast], list it with [ErrorKind::ObserverPanicked]. Default is `false`,
+    /// in which case a panicking filter unwinds through the method that was sending the event.
+    ///
+    /// Note that the panic message will still be printed by the panic hook.
+    //
+    pub fn set_catch_filter_panics(&mut self, catch: bool) {
+        self.catch_filter_panics = catch;
+    }
+
+    /// Transform every event once before it goes out to the observers, so they all get the transformed
+    /// event. This is cheaper than having every observer map the events when they all need the same change.
+    /// The transform runs before the event is cloned for each observer and before it's added to the
+    /// history. A new transform replaces the previous one.
+    //
+    pub fn set_transform(&mut self, f: impl FnMut(Event) -> Event + Send + 'static) {
+        self.transform = Some(Mutex::new(Box::new(f)));
+    }
+
+    // Apply the filter set with set_filter and the transform set with set_transform, if any. `None` if
+    // the filter rejects the event.
+    //
+    fn transform<'a>(&mut self, evt: Cow<'a, Event>) -> Option<Cow<'a, Event>> {
+        if let Some(filter) = &mut self.filter {
+            if !filter.call(&evt) {
+                return None;
+            }
+        }
+
+        let evt = match &mut self.transform {
+            Some(f) => {
+                let f = f.get_mut().unwrap_or_else(PoisonError::into_inner);
+
+                Cow::Owned(f(evt.into_owned()))
+            }
+
+            None => evt,
+        };
+
+        Some(evt)
+    }
+
+    /// Only send out the events for which `filter` returns `true`, to all observers. It runs once per event,
+    /// before the [transform](Pharos::set_transform) and the filters of the observers. `None` removes the
+    /// filter, which is the default.
+    //
+    pub fn set_filter(&mut self, filter: Option<Filter<Event>>) {
+        self.filter = filter;
+    }
+
+    /// The channel for observers that don't choose one in their [ObserveConfig]. Default is [Channel::Unbounded].
+    //
+    pub fn set_default_channel(&mut self, channel: Channel) {
+        self.default_channel = channel;
+    }
+
+    /// Configure a new pharos in one place. See [PharosBuilder].
+    //
+    pub fn builder() -> PharosBuilder<Event> {
+        PharosBuilder::default()
+    }
+
+    /// Set or remove a [CircuitBreaker]. While it is tripped, events are dropped for all observers
+    /// instead of waiting for slow observers. Setting a new breaker resets it. Default is `None`.
+    //
+    pub fn set_circuit_breaker(&mut self, breaker: Option<CircuitBreaker>) {
+        self.breaker = breaker.map(Breaker::new);
+    }
+
+    /// Whether the circuit breaker is currently tripped, meaning events are being dropped.
+    //
+    pub fn is_tripped(&self) -> bool {
+        self.breaker
+            .as_ref()
+            .map(Breaker::is_tripped)
+            .unwrap_or(false)
+    }
+
+    /// Set or remove [Batching]. While set, sent events are kept in a batch that is only delivered when flushing
+    /// finds it due, or when the pharos is closed. The interval is measured with the [Timer], without one only
+    /// full batches are delivered. The circuit breaker is not used while batching. Default is `None`.
+    ///
+    /// Events that are still in the batch are kept when changing the configuration, but dropped when
+    /// removing it, so flush first.
+    //
+    pub fn set_batching(&mut self, batching: Option<Batching>) {
+        let events = self
+            .batch
+            .take()
+            .map(Batch::into_events)
+            .unwrap_or_default();
+        let now = self.now();
+
+        self.batch = batching.map(|config| Batch::new(config, events, now));
+    }
+
+    fn now(&self) -> Option<Instant> {
+        self.timer.as_ref().map(|timer| timer.now())
+    }
+
+    // Deliver the events in the batch, waiting for the observers to be ready for each.
+    //
+    fn poll_deliver_batch(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        while self.batch.as_ref().map(|b| !b.is_empty()).unwrap_or(false) {
+            // This never fails, closed observers get removed.
+            //
+            let _ = ready!(self.poll_ready_observers(cx));
+
+            // Nobody to tell if cloning an event panics, the rest of the batch still goes out.
+            //
+            if let Some(evt) = self.batch.as_mut().and_then(Batch::pop) {
+                let _ = self.fan_out(Cow::Owned(evt), None);
+            }
+        }
+
+        Poll::Ready(())
+    }
+
+    /// The total number of events that were dropped because the circuit breaker was tripped.
+    //
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped
+    }
+
+    /// For every observer, the number of events it lost since the last call, and reset those counts
+    /// to zero. Events are lost when a [Channel::RingBuffer] drops the oldest event or when an event doesn't
+    /// fit in a full channel, eg. with [Pharos::broadcast]. Reading and resetting in one go means that
+    /// periodic reports never count a drop twice.
+    ///
+    /// There is one entry per observer, in the order of their storage slots.
+    //
+    pub fn take_dropped_counts(&mut self) -> Vec<(ObserverId, u64)> {
+        self.observers
+            .iter_mut()
+            .flatten()
+            .map(|obs| (obs.id(), obs.take_dropped()))
+            .collect()
+    }
+
+    /// Observe this pharos, starting the stream with the events from `seed`. The seed is put in the channel
+    /// before the observer is added, so it is delivered before any live events. Seed events do not pass
+    /// through the filter of the observer.
+    ///
+    /// The seed takes the place of the [replayed history](Pharos::set_replay) and the
+    /// [cached last event](Pharos::set_cache_last), the observer doesn't get those. Over the
+    /// [observer limit](Pharos::set_max_observers), it behaves like [Observable::observe].
+    ///
+    /// # Errors
+    ///
+    /// On top of the errors from [Observable::observe], this returns [ErrorKind::SeedExceedsCapacity] if the
+    /// seed does not fit in a bounded channel. In that case no observer is added.
+    //
+    pub fn observe_seeded(
+        &mut self,
+        seed: impl IntoIterator<Item = Event>,
+        options: ObserveConfig<Event>,
+    ) -> Result<Events<Event>, Error> {
+        let (events, mut sender) = match self.new_observer_or_closed(options)? {
+            (events, Some(sender)) => (events, sender),
+            (events, None) => return Ok(events),
+        };
+
+        for evt in seed {
+            if sender
+                .start_send_envelope(Envelope::new(evt, None), None)
+                .is_err()
+            {
+                return Err(ErrorKind::SeedExceedsCapacity.into());
+            }
+        }
+
+        self.insert(sender);
+
+        Ok(events)
+    }
+
+    /// Observe this pharos, only receiving the events for which `filter` returns `true`. This is a shortcut
+    /// for setting a [Filter::Closure] on `options`, which should not have a filter yet. Unlike with
+    /// [ObserveConfig::filter_boxed], the closure does not need to be `Sync`.
+    //
+    pub fn observe_filtered_fn(
+        &mut self,
+        filter: impl FnMut(&Event) -> bool + Send + 'static,
+        options: ObserveConfig<Event>,
+    ) -> Result<Events<Event>, Error> {
+        // We only ever call the filter through a mutable reference, so the mutex never gets locked.
+        // It just makes the closure Sync.
+        //
+        let mut filter = Mutex::new(filter);
+
+        self.observe(options.filter_boxed(move |evt| {
+            (filter.get_mut().unwrap_or_else(PoisonError::into_inner))(evt)
+        }))
+    }
+
+    /// Observe this pharos with a filter that is already boxed, eg. because it was put together at runtime.
+    /// The box becomes the [Filter::Closure] as is, where [ObserveConfig::filter_boxed] would box it again.
+    /// `options` should not have a filter yet.
+    //
+    pub fn observe_boxed_filter(
+        &mut self,
+        filter: Box<dyn FnMut(&Event) -> bool + Sync + Send>,
+        options: ObserveConfig<Event>,
+    ) -> Result<Events<Event>, Error> {
+        debug_assert!(
+            options.filter.is_none(),
+            "You can only set one filter on ObserveConfig"
+        );
+
+        let mut options = options;
+        options.filter = Some(Filter::Closure(filter));
+
+        self.observe(options)
+    }
+
+    /// Observe this pharos, receiving the events converted to another type with [From]. The filter
+    /// in `options` still sees the original events.
+    //
+    pub fn observe_into<U>(&mut self, options: ObserveConfig<Event>) -> Result<Events<U>, Error>
+    where
+        U: From<Event> + Clone + 'static + Sync + Send,
+    {
+        let mut options = options;
+        let id = self.new_id(&mut options)?;
+        let (events, sender) = Events::new_mapped(id, options);
+
+        self.insert(sender);
+
+        Ok(events)
+    }
+
+    /// Observe this pharos, flagging the observer in the [report of Pharos::broadcast](BroadcastReport::lagging)
+    /// while it has more than `soft_limit` events queued. Unlike the size of a bounded channel, this doesn't hold up the producer
+    /// or lose events, it only lets you know the consumer is falling behind.
+    //
+    pub fn observe_with_backlog_limit(
+        &mut self,
+        soft_limit: usize,
+        options: ObserveConfig<Event>,
+    ) -> Result<Events<Event>, Error> {
+        let (events, mut sender) = self.new_observer(options)?;
+
+        sender.set_backlog_limit(soft_limit);
+        self.insert(sender);
+
+        Ok(events)
+    }
+
+    /// Observe this pharos with a stream that tells why it ended. Events come as `Ok`, and when this pharos
+    /// is closed or dropped, or removes the observer, the stream ends with an error of kind [ErrorKind::Closed].
+    /// When the consumer closes the stream itself, it ends with `None` instead. See [ResultEvents].
+    //
+    pub fn observe_result(
+        &mut self,
+        options: ObserveConfig<Event>,
+    ) -> Result<ResultEvents<Event>, Error> {
+        self.observe(options).map(ResultEvents::new)
+    }
+
+    /// Observe this pharos, getting at most one event per `window`: the latest one that was sent during it.
+    /// Busy producers can't flood a consumer that only needs the current state, like a UI. See [Sampled].
+    ///
+    /// On top of the errors from [Observable::observe], this fails with [ErrorKind::NoTimer] if no [Timer]
+    /// is set.
+    //
+    pub fn observe_sampled_time(
+        &mut self,
+        window: Duration,
+        options: ObserveConfig<Event>,
+    ) -> Result<Sampled<Event>, Error> {
+        let timer = self.timer.clone().ok_or(ErrorKind::NoTimer)?;
+
+        self.observe(options)
+            .map(|events| Sampled::new(events, window, timer))
+    }
+
+    /// Observe this pharos with a heartbeat: when no event arrived for `interval`, the stream yields
+    /// [Heartbeat::Beat](crate::Heartbeat::Beat), so consumers can tell a quiet observable from a dead
+    /// one. Events come out as [Heartbeat::Event](crate::Heartbeat::Event). See [Heartbeats].
+    ///
+    /// On top of the errors from [Observable::observe], this fails with [ErrorKind::NoTimer] if no [Timer]
+    /// is set.
+    //
+    pub fn observe_heartbeat(
+        &mut self,
+        interval: Duration,
+        options: ObserveConfig<Event>,
+    ) -> Result<Heartbeats<Event>, Error> {
+        let timer = self.timer.clone().ok_or(ErrorKind::NoTimer)?;
+
+        self.observe(options)
+            .map(|events| Heartbeats::new(events, interval, timer))
+    }
+
+    /// Create a [SubPharos]: a pharos that is subscribed to this one and re-broadcasts only the events
+    /// that pass `filter` to it's own observers. The subscription uses an unbounded channel.
+    //
+    pub fn subpharos(&mut self, filter: Filter<Event>) -> Result<SubPharos<Event>, Error> {
+        let upstream = self.observe(filter.into())?;
+
+        Ok(SubPharos::new(upstream))
+    }
+
+    /// Observe with a [Channel::RingBuffer] of `size` events and no filter. When the observer falls
+    /// behind, the oldest events are dropped so the newest are always kept.
+    ///
+    /// Fails with [ErrorKind::MinChannelSizeOne] if `size` is zero.
+    //
+    pub fn observe_bounded_lossy(&mut self, size: usize) -> Result<Events<Event>, Error> {
+        self.observe(Channel::RingBuffer(size).into())
+    }
+
+    /// Observe this pharos, but tie the lifetime of the subscription to the returned [SubscriptionGuard]
+    /// rather than to the [Events] stream. When the guard is dropped, the observer gets removed the next time
+    /// the pharos looks for closed observers, even if the stream is still around, eg. parked in some task.
+    /// After that, the stream ends once the events that were already queued have been consumed.
+    //
+    pub fn subscribe_weak(
+        &mut self,
+        options: ObserveConfig<Event>,
+    ) -> Result<(Events<Event>, SubscriptionGuard), Error> {
+        let (events, mut sender) = self.new_observer(options)?;
+        let guard = sender.guard();
+
+        self.insert(sender);
+
+        Ok((events, guard))
+    }
+
+    // Verify that we can take a new observer with these options and create it.
+    //
+    fn new_observer(
+        &mut self,
+        mut options: ObserveConfig<Event>,
+    ) -> Result<(Events<Event>, Sender<Event>), Error> {
+        let id = self.new_id(&mut options)?;
+
+        Ok(Events::new(id, options))
+    }
+
+    // Like new_observer, but over the limit in graceful mode, this gives out a stream without a sender.
+    //
+    fn new_observer_or_closed(
+        &mut self,
+        options: ObserveConfig<Event>,
+    ) -> Result<(Events<Event>, Option<Sender<Event>>), Error> {
+        match self.new_observer(options) {
+            Ok((events, sender)) => Ok((events, Some(sender))),
+
+            Err(e)
+                if e.kind() == ErrorKind::TooManyObservers
+                    && self.over_limit == OverLimitBehavior::Graceful =>
+            {
+                let id = ObserverId(self.next_id);
+                self.next_id += 1;
+
+                // Without a sender the stream ends right away.
+                //
+                Ok((Events::new(id, ObserveConfig::default()).0, None))
+            }
+
+            Err(e) => Err(e),
+        }
+    }
+
+    // Verify that we can take a new observer with these options and give out its id.
+    //
+    fn new_id(&mut self, options: &mut ObserveConfig<Event>) -> Result<ObserverId, Error> {
+        self.prepare_options(options)?;
+
+        if let Some(max) = self.max_observers {
+            // Only pay for looking for closed observers when the slots in use hit the limit.
+            //
+            if self.observers.len() - self.free_slots.len() >= max && self.num_observers() >= max {
+                return Err(ErrorKind::TooManyObservers.into());
+            }
+        }
+
+        let id = ObserverId(self.next_id);
+        self.next_id += 1;
+
+        Ok(id)
+    }
+
+    // Fill in the default channel and verify the options.
+    //
+    fn prepare_options(&self, options: &mut ObserveConfig<Event>) -> Result<(), Error> {
+        if self.state == State::Closed {
+            return Err(ErrorKind::Closed.into());
+        }
+
+        let channel = *options.channel.get_or_insert(self.default_channel);
+
+        if channel == Channel::Bounded(0) || channel == Channel::RingBuffer(0) {
+            return Err(ErrorKind::MinChannelSizeOne.into());
+        }
+
+        Ok(())
+    }
+
+    /// Remove the observer with `id`. Its stream ends once it has yielded the events that were already
+    /// queued in it.
+    ///
+    /// Fails with [ErrorKind::UnknownObserver] if there is no observer with this id.
+    //
+    pub fn remove_observer(&mut self, id: ObserverId) -> Result<(), Error> {
+        let i = self
+            .observers
+            .iter()
+            .position(|opt| matches!(opt, Some(obs) if obs.id() == id))
+            .ok_or(ErrorKind::UnknownObserver)?;
+
+        self.observers[i] = None;
+        self.free_slots.push(i);
+        self.maybe_compact();
+
+        Ok(())
+    }
+
+    /// Split this pharos in a [Producer] that sends events and a [Registrar] that manages the observers,
+    /// eg. when one part of a program emits the events and another decides who gets them. Both halves
+    /// share the pharos behind a lock.
+    //
+    pub fn split(self) -> (Producer<Event>, Registrar<Event>) {
+        crate::split::split(self)
+    }
+
+    /// Swap the channel of the observer with `id` for a new one configured with `options`, eg. to reconnect
+    /// with another channel type. The new [Events] keep the same id and get every event sent from now on.
+    /// The old stream ends once it has yielded the events that were already queued in it.
+    ///
+    /// Fails with [ErrorKind::UnknownObserver] if there is no observer with this id, and with the errors of
+    /// [Observable::observe], except for [ErrorKind::TooManyObservers], since the number of observers doesn't change.
+    //
+    pub fn replace_observer(
+        &mut self,
+        id: ObserverId,
+        mut options: ObserveConfig<Event>,
+    ) -> Result<Events<Event>, Error> {
+        self.prepare_options(&mut options)?;
+
+        let i = self
+            .observers
+            .iter()
+            .position(|opt| {
+                opt.as_ref()
+                    .map(|obs| obs.id() == id && !obs.is_closed())
+                    .unwrap_or(false)
+            })
+            .ok_or(ErrorKind::UnknownObserver)?;
+
+        let (events, mut sender) = Events::new(id, options);
+        self.index_key(i, sender.key().map(ObserverKey::hash));
+
+        if let Some(old) = &mut self.observers[i] {
+            if let Some(hub) = old.take_membership() {
+                sender.set_membership(hub);
+            }
+        }
+
+        self.observers[i] = Some(sender);
+        self.debug_assert_invariants();
+
+        Ok(events)
+    }
+
+    // Store a new observer.
+    //
+    fn insert(&mut self, mut sender: Sender<Event>) {
+        let hash = sender.key().map(ObserverKey::hash);
+
+        if let Some(hub) = &self.membership {
+            announce(hub, Membership::Joined(sender.id()));
+            sender.set_membership(hub.clone());
+        }
+
+        // Try to reuse a free slot
+        //
+        let i = if let Some(i) = self.free_slots.pop() {
+            self.observers[i] = Some(sender);
+            i
+        } else {
+            self.observers.push(Some(sender));
+            self.observers.len() - 1
+        };
+
+        if let Some(max) = self.debug_max_storage {
+            debug_assert!(
+                self.observers.len() <= max,
+                "Pharos: storage_len {} exceeds the maximum of {} set with debug_assert_max_observers, are observers leaking?",
+                self.observers.len(),
+                max
+            );
+        }
+
+        self.index_key(i, hash);
+        self.debug_assert_invariants();
+    }
+
+    // Remember the slot of an observer with a key, by the hash of the key, for notify_keyed.
+    //
+    fn index_key(&mut self, i: usize, hash: Option<u64>) {
+        if let Some(hash) = hash {
+            let slots = self.keys.entry(hash).or_default();
+
+            if !slots.contains(&i) {
+                slots.push(i);
+            }
+        }
+    }
+
+    // Poll all observers for readiness. As soon as any is not ready, we are not ready.
+    //
+    // We poll every observer, even after one is pending, so that we get woken up by any of them and
+    // know all the observers that are blocking.
+    //
+    fn poll_ready_observers(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.blocking.clear();
+
+        for (i, obs) in self.observers.iter_mut().enumerate() {
+            if let Some(ref mut o) = obs {
+                match Pin::new(&mut *o).poll_ready(cx) {
+                    Poll::Ready(Ok(())) => {}
+                    Poll::Pending => self.blocking.push(o.id()),
+
+                    // Errors mean disconnected, so drop.
+                    //
+                    Poll::Ready(Err(_)) => {
+                        self.free_slots.push(i);
+
+                        *obs = None;
+                    }
+                }
+            }
+        }
+
+        self.debug_assert_invariants();
+
+        if self.blocking.is_empty() {
+            Ok(()).into()
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// The observers whose channel was full the last time [Sink::poll_ready] was called on this pharos,
+    /// in other words the observers that keep it from being ready. Lets a producer that is waiting
+    /// decide whether to wait longer or to get rid of them, eg. with [Pharos::replace_observer].
+    ///
+    /// Empty when the last call to `poll_ready` was ready.
+    //
+    pub fn blocking_observers(&self) -> Vec<ObserverId> {
+        self.blocking.clone()
+    }
+
+    /// Close the pharos like [SinkExt::close](https://docs.rs/futures-preview/0.3.0-alpha.19/futures/sink/trait.SinkExt.html#method.close),
+    /// but one group of observers at a time, in descending order of their [priority](ObserveConfig::priority).
+    /// Events waiting in a [batch](Pharos::set_batching) are delivered first. Before a group is closed, this
+    /// waits until all of its observers have taken every event queued for them. Only then is the next group
+    /// closed, so eg. durable sinks with a low priority are closed last.
+    ///
+    /// **Note**: this waits as long as the slowest reader of a group takes. An observer that stops reading
+    /// without dropping its [Events] keeps the returned future pending forever. If the returned future is
+    /// dropped before it resolves, calling this again closes the remaining groups.
+    //
+    pub async fn close_ordered(&mut self) -> Result<(), Error> {
+        // Observers get what's left in the batch before the first group is closed.
+        //
+        poll_fn(|cx| self.poll_deliver_batch(cx)).await;
+
+        self.state = State::Closed;
+
+        let mut priorities: Vec<i32> = self
+            .observers
+            .iter()
+            .flatten()
+            .map(Sender::priority)
+            .collect();
+
+        priorities.sort_unstable_by(|a, b| b.cmp(a));
+        priorities.dedup();
+
+        for priority in priorities {
+            poll_fn(|cx| self.poll_group(priority, cx, Sender::poll_drained)).await;
+            poll_fn(|cx| self.poll_group(priority, cx, |obs, cx| Pin::new(obs).poll_close(cx)))
+                .await;
+        }
+
+        Ok(())
+    }
+
+    // Call `poll` on all observers with the given priority until they are all ready. Observers for which
+    // it fails are removed.
+    //
+    fn poll_group(
+        &mut self,
+        priority: i32,
+        cx: &mut Context<'_>,
+        mut poll: impl FnMut(&mut Sender<Event>, &mut Context<'_>) -> Poll<Result<(), Error>>,
+    ) -> Poll<()> {
+        for (i, opt) in self.observers.iter_mut().enumerate() {
+            if let Some(obs) = opt {
+                if obs.priority() == priority && ready!(poll(obs, cx)).is_err() {
+                    self.free_slots.push(i);
+
+                    *opt = None;
+                }
+            }
+        }
+
+        Poll::Ready(())
+    }
+
+    /// Observe the observers of this pharos: get a [Membership::Joined] when one subscribes and a
+    /// [Membership::Left] when one is removed. Observers that were there before this is first called
+    /// are only announced when they leave. The stream uses an unbounded channel and ends when this pharos
+    /// is dropped.
+    ///
+    /// Fails with [ErrorKind::Closed] if the pharos is closed.
+    //
+    pub fn membership_events(&mut self) -> Result<Events<Membership>, Error> {
+        if self.state == State::Closed {
+            return Err(ErrorKind::Closed.into());
+        }
+
+        let observers = &mut self.observers;
+
+        self.membership
+            .get_or_insert_with(|| {
+                let hub = Arc::new(Mutex::new(Pharos::default()));
+
+                for obs in observers.iter_mut().flatten() {
+                    obs.set_membership(hub.clone());
+                }
+
+                hub
+            })
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .observe(ObserveConfig::default())
+    }
+
+    // Send a membership change without waiting. Only used on the pharos behind a MembershipHub, which
+    // only has unbounded observers.
+    //
+    pub(crate) fn announce(&mut self, evt: Event) {
+        if self.state == State::Ready {
+            let _ = self.fan_out(Cow::Owned(evt), None);
+        }
+    }
+
+    /// Allow a closed pharos to be used again. Observers that were connected when it was closed
+    /// have had their channels closed and will not receive any new events. New observers can
+    /// subscribe after this call. Does nothing if the pharos isn't closed.
+    //
+    pub fn reopen(&mut self) {
+        self.state = State::Ready;
+    }
+}
+
+/// Creates a new pharos, using 10 as the initial capacity of the vector used to store
+/// observers. If this number does really not fit your use case, call [Pharos::new].
+//
+impl<Event> Default for Pharos<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl<Event> Observable<Event> for Pharos<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    type Error = Error;
+
+    /// Will re-use slots from disconnected observers to avoid growing to much.
+    ///
+    /// TODO: provide API for the client to compact the pharos object after reducing the
+    ///       number of observers.
+    //
+    fn observe(&mut self, options: ObserveConfig<Event>) -> Result<Events<Event>, Self::Error> {
+        let no_replay = options.no_replay;
+        let replay = self.replay && !no_replay;
+
+        let (events, mut sender) = match self.new_observer_or_closed(options)? {
+            (events, Some(sender)) => (events, sender),
+            (events, None) => return Ok(events),
+        };
+
+        // The history ends with the last event, so it's only sent on its own without one.
+        //
+        if replay && !self.history.is_empty() {
+            self.replay_to(&mut sender);
+        } else if let Some(last) = self.last.as_ref().filter(|_| !no_replay) {
+            if sender.filter(last) {
+                let _ = sender.start_send_envelope(Envelope::new(last.clone(), None), None);
+            }
+        }
+
+        self.insert(sender);
+
+        Ok(events)
+    }
+}
+
+// See the documentation on Channel for how poll functions work for the channels we use.
+//
+impl<Event> Sink<Event> for Pharos<Event>
+where
+    Event: Clone + 'static + Sync + Send,
+{
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.state == State::Closed {
+            return Err(ErrorKind::Closed.into()).into();
+        }
+
+        let this = self.get_mut();
+
+        if matches!(this.yield_after, Some(n) if this.sent_since_yield >= n.max(1)) {
+            this.sent_since_yield = 0;
+            cx.waker().wake_by_ref();
+
+            return Poll::Pending;
+        }
+
+        // While batching, we only need to wait when the batch is full.
+        //
+        if let Some(batch) = &this.batch {
+            if batch.is_full() {
+                ready!(this.poll_deliver_batch(cx));
+            }
+
+            return Ok(()).into();
+        }
+
+        // While the circuit breaker is tripped we don't wait for anyone, the event will be dropped.
+        //
+        if this.is_tripped() {
+            return Ok(()).into();
+        }
+
+        match this.poll_ready_observers(cx) {
+            Poll::Pending => {
+                // When we trip, we are ready for the next event, which will be dropped.
+                //
+                if let Some(breaker) = &mut this.breaker {
+                    if breaker.on_pending() {
+                        return Ok(()).into();
+                    }
+                }
+
+                Poll::Pending
+            }
+
+            ready => {
+                if let Some(breaker) = &mut this.breaker {
+                    breaker.on_ready();
+                }
+
+                ready
+            }
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, evt: Event) -> Result<(), Self::Error> {
+        self.get_mut().start_send_cow(Cow::Owned(evt))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.state == State::Closed {
+            return Err(ErrorKind::Closed.into()).into();
+        }
+
+        // We loop over all, polling them all. If any return pending, we return pending.
+        // If any return an error, we drop them.
+        //
+        let mut pending = false;
+        let this = self.get_mut();
+        let now = this.now();
+
+        if this.batch.as_ref().map(|b| b.is_due(now)).unwrap_or(false) {
+            ready!(this.poll_deliver_batch(cx));
+        }
+
+        for (i, opt) in this.observers.iter_mut().enumerate() {
+            if let Some(ref mut obs) = opt {
+                match Pin::new(obs).poll_flush(cx) {
+                    Poll::Pending => pending = true,
+                    Poll::Ready(Ok(_)) => continue,
+
+                    Poll::Ready(Err(_)) => {
+                        this.free_slots.push(i);
+
+                        *opt = None;
+                    }
+                }
+            }
+        }
+
+        if pending {
+            Poll::Pending
+        } else {
+            Ok(()).into()
+        }
+    }
+
+    /// Will close and drop all observers. The pharos object will remain operational however.
+    /// The main annoyance would be that we'd have to make
+    //
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.state == State::Closed {
+            return Ok(()).into();
+        }
+
+        // Observers get what's left in the batch before they are closed.
+        //
+        ready!(self.poll_deliver_batch(cx));
+
+        self.state = State::Closed;
+
+        let this = self.get_mut();
+
+        for (i, opt) in this.observers.iter_mut().enumerate() {
+            if let Some(ref mut obs) = opt {
+                let res = ready!(Pin::new(obs).poll_close(cx));
+
+                if res.is_err() {
+                    this.free_slots.push(i);
+
+                    *opt = None;
+                }
+            }
+        }
+
+        Ok(()).into()
+    }
+}
+
+#[cfg(test)]
+// The older tests pass the context by reference and assert on constants.
+//
+#[allow(clippy::needless_borrow, clippy::assertions_on_constants)]
+//
+mod tests {
+    // Tested:
+    //
+    // - ✔ debug impl shows generic type
+    // - ✔ storage length and free slots bookkeeping
+    // - ✔ observe: we actually reuse free slots
+    // - ✔ observe: cannot observe after calling close
+    // - ✔ is_closed reflects close and reopen
+    // - ✔ observe: refuse Channel::Bounded(0)
+    // - ✔ poll_ready have a channel that is full, verify we return pending.
+    // - ✔ poll_ready have a channel that is disconnected, verify we drop it.
+    // - ✔ poll_ready should return closed if the pharos is closed.
+    // - ✔ start_send verify message arrives
+    // - ✔ start_send drop disconnected channel
+    // - ✔ start_send filter message
+    // - ✔ notify_acked resolves only after consumption
+    // - ✔ notify_acked_timeout reports the observers that didn't take the event in time
+    // - ✔ recent returns what is available, in order
+    // - ✔ a panicking filter only removes its own observer when catching panics
+    // - ✔ broadcast reports observers with a panicking filter or transform as ObserverPanicked
+    // - ✔ subscribe_weak: dropping the guard frees the slot
+    // - ✔ circuit breaker trips on repeated pending, drops events during cooldown
+    // - ✔ observe_seeded: seed arrives before live events, refuse seeds that don't fit, the seed replaces replay
+    // - ✔ export_prometheus has all metrics with values
+    // - ✔ wait_closed resolves on close and on drop
+    // - ✔ paused observers are skipped until resumed
+    // - ✔ observe_bounded_lossy drops the oldest events
+    // - ✔ peak_depths keeps the high-water mark until reset
+    // - ✔ send_timeout times out with a mock timer, fails without a timer
+    // - ✔ shrink_to reduces capacity, but not below the storage length
+    // - ✔ notify_parallel delivers to all and aggregates reports, lost tasks remove their observers
+    // - ✔ notify_parallel frees the slots when dropped mid-flight and stops when cloning panics
+    // - ✔ set_max_observers: observe fails over the limit, closed observers don't count
+    // - ✔ observer_metadata by id, downcast to the wrong type
+    // - ✔ send_if_ready sends when all are ready, reports blocked observers otherwise
+    // - ✔ last_gap reports events dropped by a ring buffer, a full bounded channel or while paused
+    // - ✔ observe_filtered_fn with a closure that isn't Sync
+    // - ✔ observe_boxed_filter with a filter assembled at runtime
+    // - ✔ close_ordered closes the group with the highest priority first, waiting for it to take its events
+    // - ✔ close_ordered delivers the batch first and waits for unbounded observers to drain
+    // - ✔ stateful closure filter keeps its state between events
+    // - ✔ set_shuffle_seed visits observers in a reproducible random order
+    // - ✔ is_empty after observe and after observers are dropped and removed
+    // - ✔ membership_events announces observers joining and leaving, but not itself
+    // - ✔ set_batching delivers events together once the interval passed or the batch is full
+    // - ✔ broadcast doesn't wait, reports SendError for a full observer and keeps it
+    // - ✔ observe_into widens the events
+    // - ✔ debug_assert_max_observers panics in debug builds only
+    // - ✔ replace_observer hands over to a new stream with the same id, unknown ids fail
+    // - ✔ send_any delivers to the ready observer while skipping the full one without stalling
+    // - ✔ broadcast and notify_acked name the observers that failed
+    // - ✔ blocking_observers reports the full observer while poll_ready is pending
+    // - ✔ events are not cloned when there is only one observer
+    // - ✔ auto compaction shrinks the storage once too many slots are free, keeping observers and keys working
+    // - ✔ send_cow clones an owned event one time less than there are observers
+    // - ✔ set_transform changes the event once for all observers
+    // - ✔ overflow_to hands events that don't fit to the callback without blocking the producer
+    // - ✔ events past their TTL or deadline are skipped by the observers that read them too late
+    // - ✔ notify_acked neither counts nor fails observers that skip an expired event
+    // - ✔ the slot bookkeeping holds up through observing, sending, dropping and compacting, and corruption is caught
+    // - ✔ replay gives new observers the history, unless they ask for no_replay
+    // - ✔ replay is cut short for a bounded channel that is too small, which shows as a gap
+    // - ✔ a panic in clone stops the event from going out further, but leaves the pharos usable
+    // - ✔ broadcast reports observers with more events queued than their backlog limit as lagging, also when it fails
+    // - ✔ take_dropped_counts reports the events each observer lost and resets the counts
+    // - ✔ max_errors removes an observer that keeps failing on broadcast within the window, or in a row without a timer
+    // - ✔ len counts observers until the ones that went away are removed
+    // - ✔ a task polling several ring buffer or tiered observers is woken once per event, default channels wake it for each
+    // - ✔ set_yield_after makes the producer yield once after every n events
+    // - ✔ an observer with a transform gets its own view of the events, others are not affected
+    // - ✔ observers over the limit get a closed stream in graceful mode
+    // - ✔ cache_last gives new observers the last event before live ones, if their filter wants it
+    // - ✔ observers that subscribe while events go out get each of them once, replayed or live
+    // - ✔ notify_keyed only reaches the observers under that key, also when slots get reused
+    // - ✔ poll_flush drop on error
+    // - ✔ Pharos and Events are Send and Sync for any event type
+    //
+    use crate::{import::*, *};
+    use static_assertions::assert_impl_all;
+
+    // So they can be moved into spawned tasks and shared between threads.
+    //
+    assert_impl_all!(Pharos<usize>: Send, Sync, Unpin);
+
+    // The same for any event type, not just usize.
+    //
+    fn _send_sync<Event: Clone + 'static + Sync + Send>() {
+        fn check<T: Send + Sync>() {}
+
+        check::<Pharos<Event>>();
+        check::<Events<Event>>();
+    }
+
+    #[test]
+    //
+    fn debug() {
+        let lighthouse = Pharos::<bool>::default();
+
+        assert_eq!("pharos::Pharos<bool>", &format!("{:?}", lighthouse));
+    }
+
+    // #[test]
+    // //
+    // fn size_of_sender()
+    // {
+    // 	dbg!( std::mem::size_of::<Option<Sender<bool>>>() );
+    // 	dbg!( std::mem::size_of::<Events<bool>>() );
+    // }
+
+    // verify storage_len and num_observers
+    //
+    #[test]
+    //
+    fn new() {
+        let ph = Pharos::<bool>::new(5);
+
+        assert_eq!(ph.observers.capacity(), 5);
+    }
+
+    // verify storage_len and num_observers
+    //
+    #[test]
+    //
+    fn storage_len() {
+        let mut ph = Pharos::<bool>::default();
+
+        assert_eq!(ph.storage_len(), 0);
+        assert_eq!(ph.num_observers(), 0);
+        assert_eq!(ph.free_slots.len(), 0);
+
+        let mut a = ph.observe(ObserveConfig::default()).expect("observe");
+
+        assert_eq!(ph.storage_len(), 1);
+        assert_eq!(ph.num_observers(), 1);
+        assert_eq!(ph.free_slots.len(), 0);
+
+        let b = ph.observe(ObserveConfig::default()).expect("observe");
+
+        assert_eq!(ph.storage_len(), 2);
+        assert_eq!(ph.num_observers(), 2);
+        assert_eq!(ph.free_slots.len(), 0);
+
+        a.close();
+
+        assert_eq!(ph.storage_len(), 2);
+        assert_eq!(ph.num_observers(), 1);
+        assert_eq!(&ph.free_slots, &[0]);
+
+        drop(b);
+
+        assert_eq!(ph.storage_len(), 2);
+        assert_eq!(ph.num_observers(), 0);
+        assert_eq!(&ph.free_slots, &[0, 1]);
+    }
+
+    // observe: Make sure we are reusing slots
+    //
+    #[test]
+    //
+    fn reuse() {
+        let mut ph = Pharos::<bool>::default();
+        let _a = ph.observe(ObserveConfig::default());
+        let b = ph.observe(ObserveConfig::default());
+        let _c = ph.observe(ObserveConfig::default());
+
+        assert_eq!(ph.storage_len(), 3);
+        assert_eq!(ph.num_observers(), 3);
+
+        drop(b);
+
+        // It's important we call num_observers here, to clear the dropped one
+        //
+        assert_eq!(ph.storage_len(), 3);
+        assert_eq!(ph.num_observers(), 2);
+
+        assert!(ph.observers[1].is_none());
+        assert_eq!(&ph.free_slots, &[1]);
+
+        let _d = ph.observe(ObserveConfig::default());
+
+        assert_eq!(ph.storage_len(), 3);
+        assert_eq!(ph.num_observers(), 3);
+        assert_eq!(ph.free_slots.len(), 0);
+
+        let _e = ph.observe(ObserveConfig::default());
+
+        // Now we should have pushed again
+        //
+        assert_eq!(ph.storage_len(), 4);
+        assert_eq!(ph.num_observers(), 4);
+        assert_eq!(ph.free_slots.len(), 0);
+    }
+
+    // observe: verify we can no longer observe after calling close
+    //
+    #[test]
+    //
+    fn observe_after_close() {
+        let mut ph = Pharos::<bool>::default();
+
+        block_on(ph.close()).expect("close");
+
+        let res = ph.observe(ObserveConfig::default());
+
+        assert!(res.is_err());
+        assert_eq!(ErrorKind::Closed, res.unwrap_err().kind());
+    }
+
+    // is_closed: false initially, true after close, false again after reopen.
+    //
+    #[test]
+    //
+    fn is_closed() {
+        let mut ph = Pharos::<bool>::default();
+
+        assert!(!ph.is_closed());
+
+        block_on(ph.close()).expect("close");
+
+        assert!(ph.is_closed());
+
+        ph.reopen();
+
+        assert!(!ph.is_closed());
+        assert!(ph.observe(ObserveConfig::default()).is_ok());
+    }
+
+    // observe: refuse Channel::Bounded(0)
+    //
+    #[test]
+    //
+    fn observe_refuse_zero() {
+        let mut ph = Pharos::<bool>::default();
+
+        let res = ph.observe(Channel::Bounded(0).into());
+
+        assert!(res.is_err());
+        assert_eq!(ErrorKind::MinChannelSizeOne, res.unwrap_err().kind());
+    }
+
+    // verify that one observer blocks pharos.
+    //
+    #[test]
+    //
+    fn poll_ready_pending() {
+        block_on(poll_fn(move |mut cx| {
+            let mut ph = Pharos::default();
+
+            let _open = ph.observe(Channel::Bounded(10).into()).expect("observe");
+            let mut full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+            let _unbound = ph.observe(Channel::Unbounded.into()).expect("observe");
+
+            let mut ph = Pin::new(&mut ph);
+
+            assert_matches!(ph.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(_)));
+            assert!(ph.as_mut().start_send(true).is_ok());
+
+            assert_matches!(ph.as_mut().poll_ready(&mut cx), Poll::Pending);
+
+            assert_eq!(Pin::new(&mut full).poll_next(cx), Poll::Ready(Some(true)));
+
+            assert_matches!(ph.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(_)));
+
+            ().into()
+        }));
+    }
+
+    // pharos drops closed observers.
+    //
+    #[test]
+    //
+    fn poll_ready_drop() {
+        block_on(poll_fn(move |mut cx| {
+            let mut ph = Pharos::<bool>::default();
+
+            let _open = ph.observe(Channel::Bounded(10).into()).expect("observe");
+            let full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+            let _unbound = ph.observe(Channel::Unbounded.into()).expect("observe");
+
+            let mut ph = Pin::new(&mut ph);
+
+            drop(full);
+
+            assert_matches!(ph.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(_)));
+
+            assert!(ph.observers[1].is_none());
+            ().into()
+        }));
+    }
+
+    // poll_ready should return closed if the pharos is closed.
+    //
+    #[test]
+    //
+    fn poll_ready_closed() {
+        block_on(poll_fn(move |mut cx| {
+            let mut ph = Pharos::<bool>::default();
+
+            let mut ph = Pin::new(&mut ph);
+
+            assert_matches!(ph.as_mut().poll_close(cx), Poll::Ready(Ok(())));
+
+            let res = ph.as_mut().poll_ready(&mut cx);
+
+            assert_matches!(res, Poll::Ready(Err(_)));
+
+            match res {
+                Poll::Ready(Err(e)) => assert_eq!(ErrorKind::Closed, e.kind()),
+                _ => assert!(false, "wrong result "),
+            }
+
+            ().into()
+        }));
+    }
+
+    // start_send verify message arrives.
+    //
+    #[test]
+    //
+    fn start_send_arrive() {
+        block_on(poll_fn(move |mut cx| {
+            let mut ph = Pharos::default();
+
+            let _open = ph.observe(Channel::Bounded(10).into()).expect("observe");
+            let mut full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+            let _unbound = ph.observe(Channel::Unbounded.into()).expect("observe");
+
+            let mut ph = Pin::new(&mut ph);
+
+            assert_matches!(ph.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(_)));
+            assert!(ph.as_mut().start_send(3).is_ok());
+
+            assert_eq!(Pin::new(&mut full).poll_next(cx), Poll::Ready(Some(3)));
+
+            ().into()
+        }));
+    }
+
+    // recent returns the buffered events in order, even if fewer than asked for.
+    //
+    #[test]
+    //
+    fn recent() {
+        let mut ph = Pharos::<usize>::default();
+
+        assert!(ph.recent(5).is_empty());
+
+        ph.set_history_len(4);
+
+        block_on(async {
+            for i in 1..=3 {
+                ph.send(i).await.expect("send");
+            }
+        });
+
+        assert_eq!(ph.recent(5), vec![1, 2, 3]);
+        assert_eq!(ph.recent(2), vec![2, 3]);
+
+        block_on(async {
+            for i in 4..=5 {
+                ph.send(i).await.expect("send");
+            }
+        });
+
+        assert_eq!(ph.recent(5), vec![2, 3, 4, 5]);
+
+        ph.set_history_len(1);
+
+        assert_eq!(ph.recent(5), vec![5]);
+    }
+
+    // A panicking filter only takes down its own observer.
+    //
+    #[test]
+    //
+    fn catch_filter_panics() {
+        let mut ph = Pharos::default();
+        ph.set_catch_filter_panics(true);
+
+        let mut a = ph.observe(ObserveConfig::default()).expect("observe");
+        let mut b = ph
+            .observe(Filter::Pointer(|_| panic!("filter")).into())
+            .expect("observe");
+        let mut c = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            ph.send(7).await.expect("send");
+
+            assert!(ph.observers[1].is_none());
+            assert_eq!(&ph.free_slots, &[1]);
+            assert_eq!(ph.num_observers(), 2);
+
+            drop(ph);
+
+            assert_eq!(Some(7), a.next().await);
+            assert_eq!(None, b.next().await);
+            assert_eq!(Some(7), c.next().await);
+        });
+    }
+
+    // Broadcast tells which observers panicked, the others still get the event.
+    //
+    #[test]
+    //
+    fn observer_panicked() {
+        let mut ph = Pharos::default();
+
+        ph.set_catch_filter_panics(true);
+
+        let a = ph.observe(ObserveConfig::default()).expect("observe");
+        let b = ph
+            .observe(Filter::Pointer(|_| panic!("filter")).into())
+            .expect("observe");
+        let c = ph
+            .observe(ObserveConfig::default().transform(|_| panic!("transform")))
+            .expect("observe");
+
+        let err = ph.broadcast(7).unwrap_err();
+
+        assert_eq!(ErrorKind::SendError, err.kind());
+        assert_eq!(2, err.observers().len());
+
+        for (id, err) in err.observers() {
+            assert!(*id == b.id() || *id == c.id());
+            assert_eq!(ErrorKind::ObserverPanicked, err.kind());
+        }
+
+        assert_eq!(1, ph.num_observers());
+
+        block_on(ph.close()).expect("close");
+
+        assert_eq!(vec![7], block_on(a.collect::<Vec<_>>()));
+    }
+
+    // Dropping the guard from subscribe_weak gets the observer reaped on the next send.
+    //
+    #[test]
+    //
+    fn subscribe_weak() {
+        let mut ph = Pharos::default();
+
+        let (mut evts, guard) = ph
+            .subscribe_weak(ObserveConfig::default())
+            .expect("observe");
+        let _other = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+
+            drop(guard);
+
+            assert_eq!(ph.storage_len(), 2);
+            assert!(ph.free_slots.is_empty());
+
+            ph.send(2).await.expect("send");
+
+            assert!(ph.observers[0].is_none());
+            assert_eq!(&ph.free_slots, &[0]);
+            assert_eq!(ph.num_observers(), 1);
+
+            // Events that were queued before still arrive.
+            //
+            assert_eq!(Some(1), evts.next().await);
+            assert_eq!(None, evts.next().await);
+        });
+    }
+
+    // The circuit breaker trips after consecutive pending polls and drops events until the
+    // cooldown is over.
+    //
+    #[test]
+    //
+    fn circuit_breaker() {
+        block_on(poll_fn(move |cx| {
+            let mut ph = Pharos::default();
+            ph.set_circuit_breaker(Some(CircuitBreaker::new(2, 3)));
+
+            let mut full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+            let mut ph = Pin::new(&mut ph);
+
+            assert_matches!(ph.as_mut().poll_ready(cx), Poll::Ready(Ok(_)));
+            ph.as_mut().start_send(1).expect("start_send");
+
+            assert_matches!(ph.as_mut().poll_ready(cx), Poll::Pending);
+            assert!(!ph.is_tripped());
+
+            // Trips now.
+            //
+            assert_matches!(ph.as_mut().poll_ready(cx), Poll::Ready(Ok(_)));
+            assert!(ph.is_tripped());
+
+            for i in 2..=4 {
+                assert_matches!(ph.as_mut().poll_ready(cx), Poll::Ready(Ok(_)));
+                ph.as_mut().start_send(i).expect("start_send");
+            }
+
+            assert!(!ph.is_tripped());
+            assert_eq!(ph.dropped_events(), 3);
+
+            // Back to normal, we wait for the observer again.
+            //
+            assert_matches!(ph.as_mut().poll_ready(cx), Poll::Pending);
+
+            assert_eq!(Pin::new(&mut full).poll_next(cx), Poll::Ready(Some(1)));
+
+            assert_matches!(ph.as_mut().poll_ready(cx), Poll::Ready(Ok(_)));
+            ph.as_mut().start_send(5).expect("start_send");
+
+            assert_eq!(Pin::new(&mut full).poll_next(cx), Poll::Ready(Some(5)));
+
+            ().into()
+        }));
+    }
+
+    // The seed arrives before any live event.
+    //
+    #[test]
+    //
+    fn observe_seeded() {
+        let mut ph = Pharos::default();
+
+        let evts = ph
+            .observe_seeded(vec![10, 20], Channel::Bounded(3).into())
+            .expect("observe");
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+
+            drop(ph);
+
+            assert_eq!(vec![10, 20, 1], evts.collect::<Vec<usize>>().await);
+        });
+    }
+
+    // The seed takes the place of the replay, and over the limit a seeded observer gets a closed stream
+    // just like any other.
+    //
+    #[test]
+    //
+    fn observe_seeded_replay() {
+        let mut ph = Pharos::default();
+
+        ph.set_history_len(2);
+        ph.set_replay(true);
+        ph.set_cache_last(true);
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            ph.send(2).await.expect("send");
+        });
+
+        let evts = ph
+            .observe_seeded(vec![10], ObserveConfig::default())
+            .expect("observe");
+
+        ph.set_max_observers(1);
+        ph.set_over_limit_behavior(OverLimitBehavior::Graceful);
+
+        let mut over = ph
+            .observe_seeded(vec![10], ObserveConfig::default())
+            .expect("observe");
+
+        block_on(async {
+            assert_eq!(None, over.next().await);
+
+            ph.send(3).await.expect("send");
+
+            drop(ph);
+
+            assert_eq!(vec![10, 3], evts.collect::<Vec<usize>>().await);
+        });
+    }
+
+    // A seed that doesn't fit the bounded channel is refused.
+    //
+    #[test]
+    //
+    fn observe_seeded_too_big() {
+        let mut ph = Pharos::default();
+
+        let res = ph.observe_seeded(vec![1, 2, 3], Channel::Bounded(2).into());
+
+        assert_eq!(ErrorKind::SeedExceedsCapacity, res.unwrap_err().kind());
+        assert_eq!(ph.storage_len(), 0);
+
+        assert!(ph
+            .observe_seeded(vec![1, 2], Channel::Bounded(2).into())
+            .is_ok());
+    }
+
+    // The prometheus export contains all metrics with their value.
+    //
+    #[cfg(feature = "metrics")]
+    //
+    #[test]
+    //
+    fn export_prometheus() {
+        let mut ph = Pharos::<bool>::default();
+
+        let _a = ph.observe(ObserveConfig::default()).expect("observe");
+        let _b = ph.observe(ObserveConfig::default()).expect("observe");
+
+        let out = ph.export_prometheus();
+
+        assert!(out.contains("# TYPE pharos_observers gauge\n"));
+        assert!(out.contains("pharos_observers{event=\"bool\"} 2\n"));
+        assert!(out.contains("pharos_free_slots{event=\"bool\"} 0\n"));
+        assert!(out.contains("pharos_storage_len{event=\"bool\"} 2\n"));
+        assert!(out.contains("# TYPE pharos_dropped_events_total counter\n"));
+        assert!(out.contains("pharos_dropped_events_total{event=\"bool\"} 0\n"));
+        assert!(out.contains("pharos_closed{event=\"bool\"} 0\n"));
+    }
+
+    // wait_closed resolves when the pharos is closed or dropped.
+    //
+    #[test]
+    //
+    fn wait_closed() {
+        let mut ph = Pharos::<bool>::default();
+        let a = ph.observe(ObserveConfig::default()).expect("observe");
+        let b = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(poll_fn(|cx| {
+            assert_eq!(Pin::new(&mut a.wait_closed()).poll(cx), Poll::Pending);
+            assert_eq!(Pin::new(&mut b.wait_closed()).poll(cx), Poll::Pending);
+
+            assert_matches!(Pin::new(&mut ph).poll_close(cx), Poll::Ready(Ok(_)));
+
+            assert_eq!(Pin::new(&mut a.wait_closed()).poll(cx), Poll::Ready(()));
+
+            ().into()
+        }));
+
+        let mut ph = Pharos::<bool>::default();
+        let mut c = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(ph.send(true)).expect("send");
+        drop(ph);
+
+        block_on(c.wait_closed());
+
+        // Queued events can still be read.
+        //
+        assert_eq!(block_on(c.next()), Some(true));
+        assert_eq!(block_on(c.next()), None);
+    }
+
+    // Paused observers miss events until they resume.
+    //
+    #[test]
+    //
+    fn pause_resume() {
+        let mut ph = Pharos::default();
+        let mut a = ph.observe(ObserveConfig::default()).expect("observe");
+        let mut b = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+
+            a.pause();
+            assert!(a.is_paused());
+
+            ph.send(2).await.expect("send");
+
+            a.resume();
+            assert!(!a.is_paused());
+
+            ph.send(3).await.expect("send");
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![1, 3], (&mut a).collect::<Vec<usize>>().await);
+            assert_eq!(vec![1, 2, 3], (&mut b).collect::<Vec<usize>>().await);
+        });
+    }
+
+    // A lossy observer keeps the newest events and never holds up the producer.
+    //
+    #[test]
+    //
+    fn observe_bounded_lossy() {
+        let mut ph = Pharos::default();
+        let lossy = ph.observe_bounded_lossy(2).expect("observe");
+
+        block_on(async {
+            for i in 1..=5 {
+                ph.send(i).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![4, 5], lossy.collect::<Vec<usize>>().await);
+        });
+
+        let res = Pharos::<usize>::default().observe_bounded_lossy(0);
+
+        assert_eq!(ErrorKind::MinChannelSizeOne, res.unwrap_err().kind());
+    }
+
+    // The peak remembers the largest burst even once the observer has caught up.
+    //
+    #[test]
+    //
+    fn peak_depths() {
+        let mut ph = Pharos::default();
+        let mut a = ph.observe(ObserveConfig::default()).expect("observe");
+        let mut b = ph.observe(Channel::RingBuffer(2).into()).expect("observe");
+
+        block_on(async {
+            for i in 0..3 {
+                ph.send(i).await.expect("send");
+            }
+
+            assert_eq!(ph.peak_depths(), vec![3, 2]);
+
+            for _ in 0..2 {
+                a.next().await;
+                b.next().await;
+            }
+
+            ph.send(3).await.expect("send");
+
+            assert_eq!(ph.peak_depths(), vec![3, 2]);
+
+            ph.reset_peaks();
+
+            assert_eq!(ph.peak_depths(), vec![2, 1]);
+        });
+    }
+
+    // send_timeout gives up on observers that don't make room in time.
+    //
+    #[test]
+    //
+    fn send_timeout() {
+        let timer = timer::MockTimer::new();
+        let mut ph = Pharos::default();
+        let mut full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+
+        ph.set_timer(Some(Arc::new(timer.clone())));
+
+        block_on(async {
+            ph.send_timeout(1, Duration::from_secs(1))
+                .await
+                .expect("send_timeout");
+        });
+
+        {
+            let mut send = Box::pin(ph.send_timeout(2, Duration::from_secs(1)));
+
+            block_on(poll_fn(|cx| {
+                assert_matches!(send.as_mut().poll(cx), Poll::Pending);
+
+                timer.advance(Duration::from_millis(500));
+                assert_matches!(send.as_mut().poll(cx), Poll::Pending);
+
+                timer.advance(Duration::from_millis(500));
+                assert_matches!(send.as_mut().poll(cx), Poll::Ready(Err(e)) if e.kind() == ErrorKind::Timeout);
+
+                ().into()
+            }));
+        }
+
+        ph.set_timer(None);
+
+        block_on(async {
+            assert_eq!(Some(1), full.next().await);
+
+            let res = ph.send_timeout(3, Duration::from_secs(1)).await;
+            assert_eq!(ErrorKind::NoTimer, res.unwrap_err().kind());
+        });
+    }
+
+    // shrink_to lowers the capacity, but never below the storage length.
+    //
+    #[test]
+    //
+    fn shrink_to() {
+        let mut ph = Pharos::<bool>::new(1000);
+
+        assert!(ph.capacity() >= 1000);
+
+        let observers: Vec<_> = (0..5)
+            .map(|_| ph.observe(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        drop(observers);
+
+        assert_eq!(ph.num_observers(), 0);
+
+        ph.shrink_to(2);
+
+        assert_eq!(ph.storage_len(), 5);
+        assert!(ph.capacity() >= 5);
+        assert!(ph.capacity() < 1000);
+
+        // Free slots are still reused.
+        //
+        let _a = ph.observe(ObserveConfig::default()).expect("observe");
+        assert_eq!(ph.storage_len(), 5);
+    }
+
+    // Spawns every task on its own thread and counts them.
+    //
+    #[derive(Default)]
+    //
+    struct ThreadSpawner {
+        spawned: AtomicUsize,
+    }
+
+    impl Spawner for ThreadSpawner {
+        fn spawn(&self, task: Pin<Box<dyn Future<Output = ()> + Send>>) {
+            self.spawned.fetch_add(1, Ordering::Relaxed);
+            std::thread::spawn(move || block_on(task));
+        }
+    }
+
+    // Loses every task.
+    //
+    struct DropSpawner;
+
+    impl Spawner for DropSpawner {
+        fn spawn(&self, task: Pin<Box<dyn Future<Output = ()> + Send>>) {
+            drop(task);
+        }
+    }
+
+    // notify_parallel delivers to all observers and sums up what happened in each task.
+    //
+    #[test]
+    //
+    fn notify_parallel() {
+        let spawner = ThreadSpawner::default();
+        let mut ph = Pharos::default();
+
+        let mut receivers: Vec<_> = (0..5)
+            .map(|_| ph.observe(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        let _odd = ph
+            .observe(Filter::Pointer(|n: &usize| n % 2 == 1).into())
+            .expect("observe");
+
+        let paused = ph.observe(ObserveConfig::default()).expect("observe");
+        paused.pause();
+
+        // Closed observers are already removed while waiting for observers to be ready.
+        //
+        drop(ph.observe(ObserveConfig::default()).expect("observe"));
+
+        let report = block_on(ph.notify_parallel(2, &spawner, 3)).expect("notify_parallel");
+
+        assert_eq!(spawner.spawned.load(Ordering::Relaxed), 3);
+        assert_eq!(
+            report,
+            FanOutReport {
+                delivered: 5,
+                skipped: 2,
+                removed: 0,
+            }
+        );
+
+        for rx in &mut receivers {
+            assert_eq!(block_on(rx.next()), Some(2));
+        }
+
+        assert_eq!(ph.num_observers(), 7);
+
+        // Observers in lost tasks are gone.
+        //
+        let report = block_on(ph.notify_parallel(3, &DropSpawner, 2)).expect("notify_parallel");
+
+        assert_eq!(report.removed, 7);
+        assert_eq!(ph.num_observers(), 0);
+        assert_eq!(block_on(receivers[0].next()), None);
+
+        // Slots are reused.
+        //
+        let _new = ph.observe(ObserveConfig::default()).expect("observe");
+        assert_eq!(ph.storage_len(), 8);
+    }
+
+    // Holds on to every task until told to run them.
+    //
+    #[derive(Default)]
+    //
+    struct HoldSpawner {
+        tasks: Mutex<Vec<Pin<Box<dyn Future<Output = ()> + Send>>>>,
+    }
+
+    impl Spawner for HoldSpawner {
+        fn spawn(&self, task: Pin<Box<dyn Future<Output = ()> + Send>>) {
+            self.tasks.lock().expect("lock").push(task);
+        }
+    }
+
+    // Dropping notify_parallel before the tasks are done frees the slots of all observers, and they
+    // announce that they left once their task is done with them.
+    //
+    #[test]
+    //
+    fn notify_parallel_dropped() {
+        let spawner = HoldSpawner::default();
+        let mut ph = Pharos::default();
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        let mut membership = ph.membership_events().expect("membership");
+
+        let observers: Vec<_> = (0..3)
+            .map(|_| ph.observe(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        {
+            let mut notify = Box::pin(ph.notify_parallel(1, &spawner, 2));
+            assert!(notify.as_mut().poll(cx).is_pending());
+        }
+
+        assert_eq!(0, ph.len());
+        assert!(ph.is_empty());
+        ph.debug_assert_invariants();
+
+        for task in spawner.tasks.lock().expect("lock").drain(..) {
+            block_on(task);
+        }
+
+        for events in &observers {
+            assert_eq!(
+                Poll::Ready(Some(Membership::Joined(events.id()))),
+                Pin::new(&mut membership).poll_next(cx)
+            );
+        }
+
+        let mut left: Vec<_> = (0..3)
+            .map(|_| match Pin::new(&mut membership).poll_next(cx) {
+                Poll::Ready(Some(Membership::Left(id))) => id,
+                other => panic!("expected Left, got {:?}", other),
+            })
+            .collect();
+
+        left.sort();
+
+        assert_eq!(observers.iter().map(Events::id).collect::<Vec<_>>(), left);
+
+        // Slots are reused.
+        //
+        let _new = ph.observe(ObserveConfig::default()).expect("observe");
+        assert_eq!(3, ph.storage_len());
+    }
+
+    // A clone that panics stops notify_parallel, and the observers that didn't get the event are kept.
+    //
+    #[test]
+    //
+    fn notify_parallel_clone_panics() {
+        let spawner = ThreadSpawner::default();
+        let mut ph = Pharos::default();
+
+        let mut observers: Vec<_> = (0..3)
+            .map(|_| ph.observe(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        // The first clone is for the task, the second one panics.
+        //
+        let res = block_on(ph.notify_parallel(Fragile(Arc::new(AtomicUsize::new(0))), &spawner, 1));
+
+        assert_eq!(ErrorKind::ClonePanicked, res.unwrap_err().kind());
+        assert_eq!(3, ph.num_observers());
+        ph.debug_assert_invariants();
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        for events in &mut observers {
+            assert!(Pin::new(events).poll_next(cx).is_pending());
+        }
+    }
+
+    // observe refuses new observers over the limit, until some leave.
+    //
+    #[test]
+    //
+    fn max_observers() {
+        let mut ph = Pharos::<bool>::default();
+
+        ph.set_max_observers(2);
+
+        let a = ph.observe(ObserveConfig::default()).expect("observe");
+        let _b = ph.observe(ObserveConfig::default()).expect("observe");
+
+        let res = ph.observe(ObserveConfig::default());
+        assert_eq!(ErrorKind::TooManyObservers, res.unwrap_err().kind());
+
+        drop(a);
+
+        let _c = ph.observe(ObserveConfig::default()).expect("observe");
+    }
+
+    // In graceful mode, observers over the limit get a stream that is over right away.
+    //
+    #[test]
+    //
+    fn over_limit_graceful() {
+        let mut ph = Pharos::<bool>::default();
+
+        ph.set_max_observers(1);
+        ph.set_over_limit_behavior(OverLimitBehavior::Graceful);
+
+        let mut a = ph.observe(ObserveConfig::default()).expect("observe");
+        let mut b = ph.observe(ObserveConfig::default()).expect("observe");
+
+        assert_ne!(a.id(), b.id());
+        assert_eq!(1, ph.num_observers());
+
+        block_on(async {
+            assert_eq!(None, b.next().await);
+            assert!(b.is_terminated());
+
+            ph.send(true).await.expect("send");
+            assert_eq!(Some(true), a.next().await);
+        });
+    }
+
+    // Metadata is found by the id of the observer and can be downcast.
+    //
+    #[test]
+    //
+    fn observer_metadata() {
+        let mut ph = Pharos::<bool>::default();
+
+        let tagged = ph
+            .observe(ObserveConfig::default().metadata(String::from("user 5")))
+            .expect("observe");
+
+        let plain = ph.observe(ObserveConfig::default()).expect("observe");
+
+        assert_ne!(tagged.id(), plain.id());
+
+        let meta = ph.observer_metadata(tagged.id()).expect("metadata");
+
+        assert_eq!(Some(&String::from("user 5")), meta.downcast_ref::<String>());
+        assert_eq!(None, meta.downcast_ref::<usize>());
+
+        assert!(ph.observer_metadata(plain.id()).is_none());
+
+        // Ids are not reused with the slot.
+        //
+        let id = tagged.id();
+        drop(tagged);
+        ph.num_observers();
+
+        let new = ph.observe(ObserveConfig::default()).expect("observe");
+
+        assert_ne!(id, new.id());
+        assert!(ph.observer_metadata(id).is_none());
+    }
+
+    // send_if_ready only sends when nobody has to wait.
+    //
+    #[test]
+    //
+    fn send_if_ready() {
+        let mut ph = Pharos::default();
+        let mut full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+        let mut other = ph.observe(ObserveConfig::default()).expect("observe");
+
+        assert_eq!(ph.send_if_ready(1).expect("send"), SendOutcome::Sent);
+        assert_eq!(
+            ph.send_if_ready(2).expect("send"),
+            SendOutcome::WouldBlock(1)
+        );
+
+        block_on(async {
+            assert_eq!(Some(1), full.next().await);
+            assert_eq!(Some(1), other.next().await);
+        });
+
+        assert_eq!(ph.send_if_ready(3).expect("send"), SendOutcome::Sent);
+
+        block_on(async {
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![3], full.collect::<Vec<usize>>().await);
+            assert_eq!(vec![3], other.collect::<Vec<usize>>().await);
+        });
+
+        assert_eq!(ErrorKind::Closed, ph.send_if_ready(4).unwrap_err().kind());
+    }
+
+    // A ring buffer that drops events shows a gap.
+    //
+    #[test]
+    //
+    fn last_gap() {
+        let mut ph = Pharos::default();
+        let mut ring = ph.observe(Channel::RingBuffer(2).into()).expect("observe");
+
+        block_on(async {
+            ph.send(0).await.expect("send");
+
+            assert_eq!(Some(0), ring.next().await);
+            assert_eq!(None, ring.last_gap());
+
+            for i in 1..=5 {
+                ph.send(i).await.expect("send");
+            }
+
+            // 1, 2 and 3 were dropped.
+            //
+            assert_eq!(Some(4), ring.next().await);
+            assert_eq!(Some((1, 4)), ring.last_gap());
+
+            assert_eq!(Some(5), ring.next().await);
+            assert_eq!(Some((1, 4)), ring.last_gap());
+        });
+    }
+
+    // Events that don't fit in a full bounded channel, or that go out while the observer is paused,
+    // show a gap too.
+    //
+    #[test]
+    //
+    fn last_gap_bounded() {
+        let mut ph = Pharos::default();
+        let mut bounded = ph.observe(Channel::Bounded(1).into()).expect("observe");
+
+        let mut fit = 0;
+
+        for i in 0..5 {
+            if ph.broadcast(i).is_ok() {
+                fit += 1;
+            }
+        }
+
+        assert!(fit < 5);
+
+        block_on(async {
+            for i in 0..fit {
+                assert_eq!(Some(i), bounded.next().await);
+            }
+
+            assert_eq!(None, bounded.last_gap());
+
+            assert_eq!(1, ph.broadcast(5).expect("broadcast").delivered);
+            assert_eq!(Some(5), bounded.next().await);
+            assert_eq!(Some((fit as u64, 5)), bounded.last_gap());
+
+            bounded.pause();
+            ph.send(6).await.expect("send");
+            bounded.resume();
+            ph.send(7).await.expect("send");
+
+            assert_eq!(Some(7), bounded.next().await);
+            assert_eq!(Some((6, 7)), bounded.last_gap());
+        });
+    }
+
+    // A closure that captures something that isn't Sync can filter events.
+    //
+    #[test]
+    //
+    fn observe_filtered_fn() {
+        let mut ph = Pharos::default();
+        let min = std::cell::Cell::new(2);
+
+        let events = ph
+            .observe_filtered_fn(move |evt| *evt >= min.get(), Channel::Bounded(5).into())
+            .expect("observe");
+
+        block_on(async {
+            for i in 1..=4 {
+                ph.send(i).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![2, 3, 4], events.collect::<Vec<_>>().await);
+        });
+    }
+
+    // A filter put together at runtime from boxed parts.
+    //
+    #[test]
+    //
+    fn observe_boxed_filter() {
+        type Predicate = Box<dyn FnMut(&usize) -> bool + Sync + Send>;
+
+        let mut ph = Pharos::default();
+
+        let mut parts: Vec<Predicate> = vec![Box::new(|evt| *evt > 1), Box::new(|evt| *evt != 3)];
+
+        let filter: Predicate = Box::new(move |evt| parts.iter_mut().all(|part| part(evt)));
+
+        let events = ph
+            .observe_boxed_filter(filter, Channel::Bounded(5).into())
+            .expect("observe");
+
+        block_on(async {
+            for i in 1..=4 {
+                ph.send(i).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![2, 4], events.collect::<Vec<_>>().await);
+        });
+    }
+
+    // Groups are closed in descending priority and only once the previous group took its events.
+    //
+    #[test]
+    //
+    fn close_ordered() {
+        fn is_closed(events: &Events<usize>, cx: &mut Context<'_>) -> bool {
+            Box::pin(events.wait_closed()).as_mut().poll(cx).is_ready()
+        }
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+        let mut ph = Pharos::default();
+        let mut order = Vec::new();
+
+        let mut store = ph.observe(Channel::Bounded(1).into()).expect("observe");
+
+        let mut ui = ph
+            .observe(ObserveConfig::from(Channel::Bounded(1)).priority(1))
+            .expect("observe");
+
+        block_on(ph.send(1)).expect("send");
+
+        {
+            let mut close = Box::pin(ph.close_ordered());
+
+            // Both channels are full, so nothing gets closed until the ui takes its event.
+            //
+            assert!(close.as_mut().poll(cx).is_pending());
+            assert!(!is_closed(&ui, cx));
+            assert!(!is_closed(&store, cx));
+
+            assert_eq!(Some(1), block_on(ui.next()));
+            assert!(close.as_mut().poll(cx).is_pending());
+
+            for (name, events) in [("ui", &ui), ("store", &store)] {
+                if is_closed(events, cx) {
+                    order.push(name);
+                }
+            }
+
+            assert_eq!(Some(1), block_on(store.next()));
+            assert_matches!(close.as_mut().poll(cx), Poll::Ready(Ok(())));
+
+            if is_closed(&store, cx) {
+                order.push("store");
+            }
+        }
+
+        assert_eq!(vec!["ui", "store"], order);
+        assert!(ph.is_closed());
+        assert_eq!(None, block_on(ui.next()));
+        assert_eq!(None, block_on(store.next()));
+    }
+
+    // The batch goes out before anything is closed, and an unbounded group is closed once it read everything.
+    //
+    #[test]
+    //
+    fn close_ordered_batching() {
+        let cx = &mut Context::from_waker(noop_waker_ref());
+        let mut ph = Pharos::default();
+
+        ph.set_timer(None);
+        ph.set_batching(Some(Batching::new(10, Duration::from_secs(60))));
+
+        let mut store = ph.observe(ObserveConfig::default()).expect("observe");
+
+        let mut ui = ph
+            .observe(ObserveConfig::default().priority(1))
+            .expect("observe");
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            ph.send(2).await.expect("send");
+        });
+
+        {
+            let mut close = Box::pin(ph.close_ordered());
+
+            assert!(close.as_mut().poll(cx).is_pending());
+
+            assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut ui).poll_next(cx));
+            assert!(close.as_mut().poll(cx).is_pending());
+
+            assert_eq!(Poll::Ready(Some(2)), Pin::new(&mut ui).poll_next(cx));
+            assert!(close.as_mut().poll(cx).is_pending());
+
+            // Only now the ui is closed, the store is still open.
+            //
+            assert_eq!(Poll::Ready(None), Pin::new(&mut ui).poll_next(cx));
+            assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut store).poll_next(cx));
+            assert_eq!(Poll::Ready(Some(2)), Pin::new(&mut store).poll_next(cx));
+            assert_eq!(Poll::Pending, Pin::new(&mut store).poll_next(cx));
+
+            assert_matches!(close.as_mut().poll(cx), Poll::Ready(Ok(())));
+        }
+
+        assert_eq!(None, block_on(store.next()));
+    }
+
+    // A filter closure keeps its state from one event to the next.
+    //
+    #[test]
+    //
+    fn stateful_filter() {
+        let mut ph = Pharos::default();
+        let mut seen = 0;
+
+        let events = ph
+            .observe(ObserveConfig::default().filter_boxed(move |_| {
+                seen += 1;
+                seen <= 2
+            }))
+            .expect("observe");
+
+        block_on(async {
+            for i in 1..=5 {
+                ph.send(i).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![1, 2], events.collect::<Vec<_>>().await);
+        });
+    }
+
+    // Collect what every observer received in one go.
+    //
+    #[cfg(feature = "testing")]
+    //
+    #[test]
+    //
+    fn drain_to_vec() {
+        let mut ph = Pharos::default();
+
+        let mut observers: Vec<_> = (0..3)
+            .map(|_| ph.observe(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        let ids: Vec<_> = observers.iter().map(Events::id).collect();
+
+        block_on(ph.send(7)).expect("send");
+
+        assert_eq!(
+            vec![(ids[0], 7), (ids[1], 7), (ids[2], 7)],
+            Pharos::drain_to_vec(&mut observers)
+        );
+        assert!(Pharos::drain_to_vec(&mut observers).is_empty());
+    }
+
+    // With a fixed seed, every send visits the observers in a known order.
+    //
+    #[cfg(feature = "chaos")]
+    //
+    #[test]
+    //
+    fn set_shuffle_seed() {
+        let mut ph = Pharos::default();
+        let visits = Arc::new(Mutex::new(Vec::new()));
+
+        let _events: Vec<_> = (0..4)
+            .map(|n| {
+                let visits = visits.clone();
+
+                ph.observe(ObserveConfig::default().filter_boxed(move |_| {
+                    visits.lock().expect("lock").push(n);
+                    true
+                }))
+                .expect("observe")
+            })
+            .collect();
+
+        ph.set_shuffle_seed(Some(7));
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            ph.send(2).await.expect("send");
+        });
+
+        assert_eq!(vec![1, 2, 0, 3, 0, 2, 1, 3], *visits.lock().expect("lock"));
+    }
+
+    // is_empty only becomes true again once dropped observers are removed.
+    //
+    #[test]
+    //
+    fn is_empty() {
+        let mut ph = Pharos::<usize>::default();
+
+        assert!(ph.is_empty());
+
+        let a = ph.observe(ObserveConfig::default()).expect("observe");
+        let b = ph.observe(ObserveConfig::default()).expect("observe");
+
+        assert!(!ph.is_empty());
+
+        drop(a);
+        drop(b);
+
+        assert!(!ph.is_empty());
+        assert_eq!(0, ph.num_observers());
+        assert!(ph.is_empty());
+    }
+
+    // Observers joining and leaving are announced, the membership observer itself is not.
+    //
+    #[test]
+    //
+    fn membership_events() {
+        let mut ph = Pharos::<usize>::default();
+
+        let before = ph.observe(ObserveConfig::default()).expect("observe");
+        let membership = ph.membership_events().expect("membership");
+        let after = ph.observe(ObserveConfig::default()).expect("observe");
+
+        let (before_id, after_id) = (before.id(), after.id());
+
+        drop(after);
+        assert_eq!(1, ph.num_observers());
+
+        drop(before);
+        drop(ph);
+
+        assert_eq!(
+            vec![
+                Membership::Joined(after_id),
+                Membership::Left(after_id),
+                Membership::Left(before_id),
+            ],
+            block_on(membership.collect::<Vec<_>>())
+        );
+    }
+
+    // Batched events arrive together at the interval boundary, when the batch is full or on close.
+    //
+    #[test]
+    //
+    fn set_batching() {
+        let timer = timer::MockTimer::new();
+        let mut ph = Pharos::default();
+        let mut events = ph
+            .observe(ObserveConfig::default())
+            .expect("observe")
+            .ready_chunks(10);
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        ph.set_timer(Some(Arc::new(timer.clone())));
+        ph.set_batching(Some(Batching::new(3, Duration::from_secs(1))));
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            timer.advance(Duration::from_millis(500));
+            ph.send(2).await.expect("send");
+        });
+
+        assert_eq!(Poll::Pending, Pin::new(&mut events).poll_next(cx));
+
+        timer.advance(Duration::from_millis(500));
+        block_on(ph.flush()).expect("flush");
+
+        assert_eq!(
+            Poll::Ready(Some(vec![1, 2])),
+            Pin::new(&mut events).poll_next(cx)
+        );
+
+        // A full batch goes out without waiting for the interval.
+        //
+        block_on(async {
+            for i in 3..=6 {
+                ph.send(i).await.expect("send");
+            }
+        });
+
+        assert_eq!(
+            Poll::Ready(Some(vec![3, 4, 5])),
+            Pin::new(&mut events).poll_next(cx)
+        );
+
+        block_on(ph.close()).expect("close");
+
+        assert_eq!(
+            Poll::Ready(Some(vec![6])),
+            Pin::new(&mut events).poll_next(cx)
+        );
+        assert_eq!(Poll::Ready(None), Pin::new(&mut events).poll_next(cx));
+    }
+
+    // broadcast offers the event to everyone and reports observers that couldn't take it.
+    //
+    #[test]
+    //
+    fn broadcast() {
+        let mut ph = Pharos::default();
+
+        let mut full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+        let mut open = ph.observe(ObserveConfig::default()).expect("observe");
+
+        assert_eq!(2, ph.broadcast(1).expect("broadcast").delivered);
+
+        let err = ph.broadcast(2).unwrap_err();
+
+        assert_eq!(ErrorKind::SendError, err.kind());
+        assert_eq!(full.id(), err.observers()[0].0);
+
+        block_on(async {
+            assert_eq!(Some(1), open.next().await);
+            assert_eq!(Some(2), open.next().await);
+
+            // The full observer missed the event, but is still subscribed.
+            //
+            assert_eq!(Some(1), full.next().await);
+            assert_eq!(2, ph.broadcast(3).expect("broadcast").delivered);
+            assert_eq!(Some(3), full.next().await);
+
+            ph.close().await.expect("close");
+        });
+
+        assert_eq!(ErrorKind::Closed, ph.broadcast(4).unwrap_err().kind());
+    }
+
+    // Observe a pharos of u8 as a stream of u32.
+    //
+    #[test]
+    //
+    fn observe_into() {
+        let mut ph = Pharos::<u8>::default();
+
+        let wide: Events<u32> = ph
+            .observe_into(ObserveConfig::default().filter(|evt| *evt > 1))
+            .expect("observe");
+
+        block_on(async {
+            for i in 1..=3 {
+                ph.send(i).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![2_u32, 3], wide.collect::<Vec<_>>().await);
+        });
+    }
+
+    // Going over the ceiling panics in debug builds.
+    //
+    #[cfg(debug_assertions)]
+    //
+    #[test]
+    #[should_panic(expected = "are observers leaking?")]
+    //
+    fn debug_assert_max_observers() {
+        let mut ph = Pharos::<usize>::default();
+        ph.debug_assert_max_observers(1);
+
+        let _a = ph.observe(ObserveConfig::default()).expect("observe");
+        let _b = ph.observe(ObserveConfig::default()).expect("observe");
+    }
+
+    // In release builds the ceiling is ignored.
+    //
+    #[cfg(not(debug_assertions))]
+    //
+    #[test]
+    //
+    fn debug_assert_max_observers() {
+        let mut ph = Pharos::<usize>::default();
+        ph.debug_assert_max_observers(1);
+
+        let _a = ph.observe(ObserveConfig::default()).expect("observe");
+        let _b = ph.observe(ObserveConfig::default()).expect("observe");
+
+        assert_eq!(2, ph.storage_len());
+    }
+
+    // After replacing an observer, events keep flowing to the new stream.
+    //
+    #[test]
+    //
+    fn replace_observer() {
+        let mut ph = Pharos::default();
+        let old = ph.observe(Channel::Bounded(5).into()).expect("observe");
+        let id = old.id();
+
+        block_on(ph.send(1)).expect("send");
+
+        let new = ph
+            .replace_observer(id, Channel::Unbounded.into())
+            .expect("replace");
+
+        assert_eq!(id, new.id());
+        assert_eq!(1, ph.num_observers());
+
+        block_on(async {
+            ph.send(2).await.expect("send");
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![1], old.collect::<Vec<_>>().await);
+            assert_eq!(vec![2], new.collect::<Vec<_>>().await);
+        });
+
+        ph.reopen();
+
+        assert_eq!(
+            ErrorKind::UnknownObserver,
+            ph.replace_observer(id, ObserveConfig::default())
+                .unwrap_err()
+                .kind()
+        );
+    }
+
+    // A full observer doesn't hold up the others with send_any.
+    //
+    #[test]
+    //
+    fn send_any() {
+        let mut ph = Pharos::default();
+
+        let mut full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+        let mut ready = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            assert_eq!(2, ph.send_any(1).await.expect("send"));
+            assert_eq!(1, ph.send_any(2).await.expect("send"));
+            assert_eq!(1, ph.send_any(3).await.expect("send"));
+
+            assert_eq!(Some(1), full.next().await);
+            assert_eq!(Some(1), ready.next().await);
+            assert_eq!(Some(2), ready.next().await);
+            assert_eq!(Some(3), ready.next().await);
+
+            // Once it has room again, the full observer gets events again.
+            //
+            assert_eq!(2, ph.send_any(4).await.expect("send"));
+            assert_eq!(Some(4), full.next().await);
+        });
+
+        // With only the full observer left, we have to wait.
+        //
+        drop(ready);
+        assert_eq!(1, ph.start_send_any(5).expect("send"));
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+        assert_matches!(ph.poll_ready_any(cx), Poll::Pending);
+    }
+
+    // The error of broadcast and notify_acked lists the observers that failed.
+    //
+    #[test]
+    //
+    fn failed_observers() {
+        let mut ph = Pharos::default();
+
+        let mut open = ph.observe(ObserveConfig::default()).expect("observe");
+        let gone = ph.observe(ObserveConfig::default()).expect("observe");
+        let gone_id = gone.id();
+
+        drop(gone);
+
+        let err = ph.broadcast(1).unwrap_err();
+
+        assert_eq!(ErrorKind::SendError, err.kind());
+        assert_eq!(1, err.observers().len());
+        assert_eq!(gone_id, err.observers()[0].0);
+        assert_eq!(ErrorKind::SendError, err.observers()[0].1.kind());
+
+        // The observer that went away before consuming the event is reported.
+        //
+        let late = ph.observe(ObserveConfig::default()).expect("observe");
+        let late_id = late.id();
+
+        block_on(async {
+            let ack = ph.notify_acked(2);
+            let consume = async move {
+                assert_eq!(Some(1), open.next().await);
+                assert_eq!(Some(2), open.next().await);
+                drop(late);
+            };
+
+            let (result, ()) = futures::join!(ack, consume);
+            let err = result.unwrap_err();
+
+            assert_eq!(
+                vec![late_id],
+                err.observers()
+                    .iter()
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<_>>()
+            );
+        });
+    }
+
+    // The full observer is reported as blocking while poll_ready is pending.
+    //
+    #[test]
+    //
+    fn blocking_observers() {
+        let mut ph = Pharos::default();
+
+        let mut full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+        let _open = ph.observe(ObserveConfig::default()).expect("observe");
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        assert!(ph.blocking_observers().is_empty());
+
+        assert_matches!(Pin::new(&mut ph).poll_ready(cx), Poll::Ready(Ok(())));
+        Pin::new(&mut ph).start_send(1).expect("send");
+
+        assert_matches!(Pin::new(&mut ph).poll_ready(cx), Poll::Pending);
+        assert_eq!(vec![full.id()], ph.blocking_observers());
+
+        // Once the observer takes the event, nobody is blocking anymore.
+        //
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut full).poll_next(cx));
+
+        assert_matches!(Pin::new(&mut ph).poll_ready(cx), Poll::Ready(Ok(())));
+        assert!(ph.blocking_observers().is_empty());
+    }
+
+    // Counts how often it gets cloned.
+    //
+    #[derive(Debug)]
+    //
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            self.0.fetch_add(1, Ordering::SeqCst);
+
+            Self(self.0.clone())
+        }
+    }
+
+    // With a single observer the event is moved into its channel. With more, the last one gets the original.
+    //
+    #[test]
+    //
+    fn clone_elision() {
+        let clones = Arc::new(AtomicUsize::new(0));
+        let mut ph = Pharos::default();
+
+        let mut first = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            ph.send(Counted(clones.clone())).await.expect("send");
+            assert_eq!(0, clones.load(Ordering::SeqCst));
+
+            let mut second = ph.observe(ObserveConfig::default()).expect("observe");
+
+            ph.send(Counted(clones.clone())).await.expect("send");
+            assert_eq!(1, clones.load(Ordering::SeqCst));
+
+            first.next().await.expect("event");
+            first.next().await.expect("event");
+            second.next().await.expect("event");
+        });
+    }
+
+    // Churning observers trips auto compaction, after which the remaining observer still works.
+    //
+    #[test]
+    //
+    fn auto_compact() {
+        let mut ph = Pharos::default();
+        ph.set_auto_compact(Some(0.5));
+
+        let mut gone: Vec<_> = (0..3)
+            .map(|_| ph.observe(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        let mut keyed = ph
+            .observe(ObserveConfig::default().key("k"))
+            .expect("observe");
+        let id = keyed.id();
+
+        assert_eq!(4, ph.storage_len());
+
+        block_on(async {
+            // Two of four free is not over the ratio yet.
+            //
+            gone.truncate(1);
+            ph.send(1).await.expect("send");
+
+            assert_eq!(4, ph.storage_len());
+
+            gone.clear();
+            ph.send(2).await.expect("send");
+
+            assert_eq!(1, ph.storage_len());
+            assert!(ph.free_slots.is_empty());
+
+            ph.notify_keyed("k", 3).await.expect("notify_keyed");
+
+            assert_eq!(id, keyed.id());
+            assert_eq!(Some(1), keyed.next().await);
+            assert_eq!(Some(2), keyed.next().await);
+            assert_eq!(Some(3), keyed.next().await);
+        });
+    }
+
+    // A borrowed event is cloned for every observer, an owned one for all but the last.
+    //
+    #[test]
+    //
+    fn send_cow() {
+        let clones = Arc::new(AtomicUsize::new(0));
+        let mut ph = Pharos::default();
+
+        let observers: Vec<_> = (0..3)
+            .map(|_| ph.observe(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        block_on(async {
+            ph.send_cow(Cow::Owned(Counted(clones.clone())))
+                .await
+                .expect("send");
+
+            assert_eq!(2, clones.swap(0, Ordering::SeqCst));
+
+            let kept = Counted(clones.clone());
+
+            ph.send_cow(Cow::Borrowed(&kept)).await.expect("send");
+
+            assert_eq!(3, clones.load(Ordering::SeqCst));
+
+            ph.close().await.expect("close");
+
+            for events in observers {
+                assert_eq!(2, events.count().await);
+            }
+        });
+    }
+
+    // The transform runs once per event, before it's cloned for the observers.
+    //
+    #[test]
+    //
+    fn set_transform() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+
+        let mut ph = Pharos::default();
+
+        ph.set_transform(move |n: usize| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            n + 1
+        });
+
+        let observers: Vec<_> = (0..3)
+            .map(|_| ph.observe(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            ph.send(2).await.expect("send");
+            ph.close().await.expect("close");
+
+            for events in observers {
+                assert_eq!(vec![2, 3], events.collect::<Vec<_>>().await);
+            }
+        });
+
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+
+    // A full observer with an overflow callback doesn't make the producer wait.
+    //
+    #[test]
+    //
+    fn overflow_to() {
+        let spilled = Arc::new(Mutex::new(Vec::new()));
+        let sink = spilled.clone();
+
+        let mut ph = Pharos::default();
+
+        let events = ph
+            .observe(
+                ObserveConfig::from(Channel::Bounded(1))
+                    .overflow_to(move |evt| sink.lock().expect("lock").push(evt)),
+            )
+            .expect("observe");
+
+        block_on(async {
+            for i in 1..=4 {
+                ph.send(i).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![1], events.collect::<Vec<_>>().await);
+        });
+
+        assert_eq!(vec![2, 3, 4], *spilled.lock().expect("lock"));
+    }
+
+    // A slow observer skips the events that expired before it got to them.
+    //
+    #[test]
+    //
+    fn ttl() {
+        let timer = timer::MockTimer::new();
+        let mut ph = Pharos::default();
+
+        ph.set_timer(Some(Arc::new(timer.clone())));
+        ph.set_ttl(Some(Duration::from_secs(1)));
+
+        let mut fast = ph.observe(ObserveConfig::default()).expect("observe");
+        let mut slow = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+            assert_eq!(Some(1), fast.next().await);
+
+            timer.advance(Duration::from_secs(2));
+
+            ph.send(2).await.expect("send");
+
+            let deadline = timer.now() + Duration::from_secs(5);
+            ph.send_with_deadline(3, deadline).await.expect("send");
+
+            timer.advance(Duration::from_secs(2));
+
+            ph.close().await.expect("close");
+
+            assert_eq!(Some(3), slow.next().await);
+            assert_eq!(None, slow.last_gap());
+            assert_eq!(None, slow.next().await);
+
+            assert_eq!(vec![3], fast.collect::<Vec<_>>().await);
+        });
+    }
+
+    // An observer that skips an event because it expired is neither counted nor failed by notify_acked.
+    //
+    #[test]
+    //
+    fn ttl_acked() {
+        let timer = timer::MockTimer::new();
+        let mut ph = Pharos::default();
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        ph.set_timer(Some(Arc::new(timer.clone())));
+        ph.set_ttl(Some(Duration::from_secs(1)));
+
+        let mut fast = ph.observe(ObserveConfig::default()).expect("observe");
+        let mut slow = ph.observe(ObserveConfig::default()).expect("observe");
+
+        let mut notify = Box::pin(ph.notify_acked(1));
+
+        assert!(notify.as_mut().poll(cx).is_pending());
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut fast).poll_next(cx));
+        assert!(notify.as_mut().poll(cx).is_pending());
+
+        timer.advance(Duration::from_secs(2));
+
+        assert_eq!(Poll::Pending, Pin::new(&mut slow).poll_next(cx));
+        assert_matches!(notify.as_mut().poll(cx), Poll::Ready(Ok(1)));
+    }
+
+    // Every operation checks the bookkeeping in debug builds, so a churn of observers must pass.
+    //
+    #[cfg(debug_assertions)]
+    //
+    #[test]
+    //
+    fn invariants() {
+        let mut ph = Pharos::default();
+
+        ph.set_auto_compact(Some(0.5));
+
+        let keyed = ph
+            .observe(ObserveConfig::default().key("kept"))
+            .expect("observe");
+
+        let mut kept = Vec::new();
+
+        block_on(async {
+            for i in 0..10 {
+                let events = ph.observe(ObserveConfig::default()).expect("observe");
+
+                // The others are dropped, so their slots get freed and reused.
+                //
+                if i % 2 == 0 {
+                    kept.push(events);
+                }
+
+                ph.send(i).await.expect("send");
+                ph.notify_keyed("kept", i).await.expect("notify_keyed");
+            }
+
+            let _replaced = ph
+                .replace_observer(keyed.id(), ObserveConfig::default())
+                .expect("replace");
+
+            assert_eq!(6, ph.num_observers());
+            assert_eq!(6, ph.broadcast(10).expect("broadcast").delivered);
+        });
+
+        ph.check_invariants();
+    }
+
+    // An observer whose slot is also listed as free would get overwritten.
+    //
+    #[cfg(debug_assertions)]
+    //
+    #[test]
+    #[should_panic(expected = "free slot 0 holds an observer")]
+    //
+    fn corrupt_free_slots() {
+        let mut ph = Pharos::<usize>::default();
+        let _events = ph.observe(ObserveConfig::default()).expect("observe");
+
+        ph.free_slots.push(0);
+        ph.check_invariants();
+    }
+
+    // Observers that opt out of the replay only get the events sent after they subscribed.
+    //
+    #[test]
+    //
+    fn no_replay() {
+        let mut ph = Pharos::default();
+
+        ph.set_history_len(3);
+        ph.set_replay(true);
+
+        block_on(async {
+            for i in 1..=4 {
+                ph.send(i).await.expect("send");
+            }
+
+            let replayed = ph.observe(ObserveConfig::default()).expect("observe");
+            let live = ph
+                .observe(ObserveConfig::default().no_replay())
+                .expect("observe");
+
+            ph.send(5).await.expect("send");
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![2, 3, 4, 5], replayed.collect::<Vec<_>>().await);
+            assert_eq!(vec![5], live.collect::<Vec<_>>().await);
+        });
+    }
+
+    // Panics on the second clone.
+    //
+    #[derive(Debug)]
+    //
+    struct Fragile(Arc<AtomicUsize>);
+
+    impl Clone for Fragile {
+        fn clone(&self) -> Self {
+            if self.0.fetch_add(1, Ordering::SeqCst) == 1 {
+                panic!("Fragile: second clone");
+            }
+
+            Self(self.0.clone())
+        }
+    }
+
+    // The observers before the panic keep the event, the pharos stays consistent and keeps working.
+    //
+    #[test]
+    //
+    fn clone_panics() {
+        let clones = Arc::new(AtomicUsize::new(0));
+        let mut ph = Pharos::default();
+
+        let mut observers: Vec<_> = (0..3)
+            .map(|_| ph.observe(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        block_on(async {
+            let res = ph.send(Fragile(clones.clone())).await;
+
+            assert_eq!(ErrorKind::ClonePanicked, res.unwrap_err().kind());
+        });
+
+        ph.debug_assert_invariants();
+
+        let received: Vec<_> = observers
+            .iter_mut()
+            .map(|events| Pin::new(events).poll_next(cx).is_ready())
+            .collect();
+
+        assert_eq!(vec![true, false, false], received);
+
+        block_on(async {
+            ph.send(Fragile(Arc::new(AtomicUsize::new(5))))
+                .await
+                .expect("send");
+
+            ph.close().await.expect("close");
+        });
+
+        for events in observers {
+            assert_eq!(1, block_on(events.count()));
+        }
+    }
+
+    // An observer is flagged while it's over its soft limit, and events keep coming up to the channel size.
+    //
+    #[test]
+    //
+    fn backlog_limit() {
+        let mut ph = Pharos::default();
+
+        let mut slow = ph
+            .observe_with_backlog_limit(2, Channel::Bounded(4).into())
+            .expect("observe");
+
+        let _other = ph.observe(ObserveConfig::default()).expect("observe");
+
+        for i in 1..=2 {
+            let report = ph.broadcast(i).expect("broadcast");
+
+            assert_eq!(2, report.delivered);
+            assert!(report.lagging.is_empty());
+        }
+
+        assert_eq!(vec![slow.id()], ph.broadcast(3).expect("broadcast").lagging);
+        assert_eq!(vec![slow.id()], ph.broadcast(4).expect("broadcast").lagging);
+
+        block_on(async {
+            for i in 1..=3 {
+                assert_eq!(Some(i), slow.next().await);
+            }
+        });
+
+        assert!(ph.broadcast(5).expect("broadcast").lagging.is_empty());
+    }
+
+    // When another observer is full, broadcast fails, but the error still tells who is lagging.
+    //
+    #[test]
+    //
+    fn backlog_limit_with_full_observer() {
+        let mut ph = Pharos::default();
+
+        let full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+
+        let slow = ph
+            .observe_with_backlog_limit(1, Channel::Bounded(4).into())
+            .expect("observe");
+
+        assert_eq!(2, ph.broadcast(1).expect("broadcast").delivered);
+
+        let err = ph.broadcast(2).expect_err("broadcast");
+
+        assert_eq!(full.id(), err.observers()[0].0);
+
+        assert_eq!(
+            Some(&BroadcastReport {
+                delivered: 1,
+                lagging: vec![slow.id()],
+            }),
+            err.report()
+        );
+    }
+
+    // Counts come from ring buffer evictions and full channels, and start over after being taken.
+    //
+    #[test]
+    //
+    fn take_dropped_counts() {
+        let mut ph = Pharos::default();
+
+        let ring = ph.observe(Channel::RingBuffer(2).into()).expect("observe");
+        let bounded = ph.observe(Channel::Bounded(1).into()).expect("observe");
+        let unbounded = ph.observe(Channel::Unbounded.into()).expect("observe");
+
+        for i in 1..=5 {
+            let _ = ph.broadcast(i);
+        }
+
+        assert_eq!(
+            vec![(ring.id(), 3), (bounded.id(), 4), (unbounded.id(), 0)],
+            ph.take_dropped_counts()
+        );
+
+        assert_eq!(
+            vec![(ring.id(), 0), (bounded.id(), 0), (unbounded.id(), 0)],
+            ph.take_dropped_counts()
+        );
+    }
+
+    // An observer that keeps failing is removed once it failed more than max_errors times within the window,
+    // even when some events get through in between. Failures that are older don't count.
+    //
+    #[test]
+    //
+    fn max_errors() {
+        let timer = timer::MockTimer::new();
+        let mut ph = Pharos::default();
+        let window = Duration::from_secs(10);
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        ph.set_timer(Some(Arc::new(timer.clone())));
+
+        let mut flaky = ph
+            .observe(ObserveConfig::from(Channel::Bounded(1)).max_errors(2, window))
+            .expect("observe");
+
+        // Every other event doesn't fit.
+        //
+        for i in 0..3 {
+            assert_eq!(1, ph.broadcast(i * 2).expect("broadcast").delivered);
+            assert!(ph.broadcast(i * 2 + 1).is_err());
+            assert_eq!(Poll::Ready(Some(i * 2)), Pin::new(&mut flaky).poll_next(cx));
+        }
+
+        assert_eq!(0, ph.num_observers());
+
+        let mut slow = ph
+            .observe(ObserveConfig::from(Channel::Bounded(1)).max_errors(2, window))
+            .expect("observe");
+
+        assert_eq!(1, ph.broadcast(0).expect("broadcast").delivered);
+
+        // Failures that are spread out never add up.
+        //
+        for i in 1..5 {
+            assert!(ph.broadcast(i).is_err());
+            timer.advance(window / 2);
+        }
+
+        assert_eq!(1, ph.num_observers());
+
+        assert!(ph.broadcast(5).is_err());
+        assert!(ph.broadcast(6).is_err());
+        assert_eq!(0, ph.num_observers());
+
+        assert_eq!(Poll::Ready(Some(0)), Pin::new(&mut slow).poll_next(cx));
+    }
+
+    // Without a timer, only failures in a row count.
+    //
+    #[test]
+    //
+    fn max_errors_no_timer() {
+        let mut ph = Pharos::default();
+
+        ph.set_timer(None);
+
+        let stuck = ph
+            .observe(ObserveConfig::from(Channel::Bounded(1)).max_errors(2, Duration::from_secs(1)))
+            .expect("observe");
+
+        let mut slow = ph
+            .observe(ObserveConfig::from(Channel::Bounded(1)).max_errors(2, Duration::from_secs(1)))
+            .expect("observe");
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        assert_eq!(2, ph.broadcast(1).expect("broadcast").delivered);
+        assert!(ph.broadcast(2).is_err());
+        assert!(ph.broadcast(3).is_err());
+
+        // The third failure in a row is one too many for the stuck observer. The slow one made room,
+        // which resets its count.
+        //
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut slow).poll_next(cx));
+        assert_eq!(1, ph.broadcast(4).expect_err("broadcast").observers().len());
+        assert_eq!(1, ph.num_observers());
+
+        assert!(ph.broadcast(5).is_err());
+        assert!(ph.broadcast(6).is_err());
+        assert_eq!(1, ph.num_observers());
+
+        assert!(ph.broadcast(7).is_err());
+        assert_eq!(0, ph.num_observers());
+
+        block_on(ph.close()).expect("close");
+
+        assert_eq!(vec![1], block_on(stuck.collect::<Vec<_>>()));
     }
 
-    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        if self.state == State::Closed {
-            return Err(ErrorKind::Closed.into()).into();
-        }
+    // len follows observe and removal, but doesn't notice dropped observers on its own.
+    //
+    #[test]
+    //
+    fn len() {
+        let mut ph = Pharos::<usize>::default();
 
-        // We loop over all, polling them all. If any return pending, we return pending.
-        // If any return an error, we drop them.
+        assert_eq!(0, ph.len());
+
+        let a = ph.observe(ObserveConfig::default()).expect("observe");
+        let b = ph.observe(ObserveConfig::default()).expect("observe");
+
+        assert_eq!(2, ph.len());
+
+        drop(a);
+
+        assert_eq!(2, ph.len());
+        assert_eq!(1, ph.num_observers());
+        assert_eq!(1, ph.len());
+
+        // The free slot gets reused.
         //
-        let mut pending = false;
-        let this = self.get_mut();
+        let c = ph.observe(ObserveConfig::default()).expect("observe");
 
-        for (i, opt) in this.observers.iter_mut().enumerate() {
-            if let Some(ref mut obs) = opt {
-                match Pin::new(obs).poll_flush(cx) {
-                    Poll::Pending => pending = true,
-                    Poll::Ready(Ok(_)) => continue,
+        assert_eq!(2, ph.len());
 
-                    Poll::Ready(Err(_)) => {
-                        this.free_slots.push(i);
+        drop(b);
+        drop(c);
 
-                        *opt = None;
-                    }
-                }
-            }
-        }
+        block_on(ph.send(1)).expect("send");
 
-        if pending {
-            Poll::Pending
-        } else {
-            Ok(()).into()
-        }
+        assert_eq!(0, ph.len());
+        assert!(ph.is_empty());
     }
 
-    /// Will close and drop all observers. The pharos object will remain operational however.
-    /// The main annoyance would be that we'd have to make
+    // Two observers polled by the same task wake it once per event, whichever way the event goes out.
     //
-    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        if self.state == State::Closed {
-            return Ok(()).into();
-        } else {
-            self.state = State::Closed;
+    #[test]
+    //
+    fn wake_once() {
+        use futures::task::{waker, ArcWake};
+
+        struct Count(AtomicUsize);
+
+        impl ArcWake for Count {
+            fn wake_by_ref(arc_self: &Arc<Self>) {
+                arc_self.0.fetch_add(1, Ordering::SeqCst);
+            }
         }
 
-        let this = self.get_mut();
+        let count = Arc::new(Count(AtomicUsize::new(0)));
+        let waker = waker(count.clone());
+        let cx = &mut Context::from_waker(&waker);
 
-        for (i, opt) in this.observers.iter_mut().enumerate() {
-            if let Some(ref mut obs) = opt {
-                let res = ready!(Pin::new(obs).poll_close(cx));
+        let mut ph = Pharos::default();
+        let mut a = ph.observe(Channel::RingBuffer(4).into()).expect("observe");
+        let mut b = ph
+            .observe(
+                ObserveConfig::default()
+                    .channel(Channel::Bounded(4))
+                    .reserve(1, |_| true),
+            )
+            .expect("observe");
 
-                if res.is_err() {
-                    this.free_slots.push(i);
+        assert_eq!(Poll::Pending, Pin::new(&mut a).poll_next(cx));
+        assert_eq!(Poll::Pending, Pin::new(&mut b).poll_next(cx));
 
-                    *opt = None;
-                }
-            }
-        }
+        assert_eq!(2, ph.broadcast(1).expect("broadcast").delivered);
+        assert_eq!(1, count.0.load(Ordering::SeqCst));
 
-        Ok(()).into()
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut a).poll_next(cx));
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut b).poll_next(cx));
+        assert_eq!(Poll::Pending, Pin::new(&mut a).poll_next(cx));
+        assert_eq!(Poll::Pending, Pin::new(&mut b).poll_next(cx));
+
+        block_on(ph.send(2)).expect("send");
+        assert_eq!(2, count.0.load(Ordering::SeqCst));
     }
-}
 
-#[cfg(test)]
-//
-mod tests {
-    // Tested:
-    //
-    // - ✔ debug impl shows generic type
-    // - ✔ storage length and free slots bookkeeping
-    // - ✔ observe: we actually reuse free slots
-    // - ✔ observe: cannot observe after calling close
-    // - ✔ observe: refuse Channel::Bounded(0)
-    // - ✔ poll_ready have a channel that is full, verify we return pending.
-    // - ✔ poll_ready have a channel that is disconnected, verify we drop it.
-    // - ✔ poll_ready should return closed if the pharos is closed.
-    // - ✔ start_send verify message arrives
-    // - ✔ start_send drop disconnected channel
-    // - ✔ start_send filter message
-    // - ✔ poll_flush drop on error
+    // The channels from futures_channel wake the receiver themselves, so a task polling two default
+    // observers is woken for each of them.
     //
-    use crate::{import::*, *};
-
     #[test]
     //
-    fn debug() {
-        let lighthouse = Pharos::<bool>::default();
+    fn wake_once_default_channel() {
+        use futures::task::{waker, ArcWake};
 
-        assert_eq!("pharos::Pharos<bool>", &format!("{:?}", lighthouse));
-    }
+        struct Count(AtomicUsize);
 
-    // #[test]
-    // //
-    // fn size_of_sender()
-    // {
-    // 	dbg!( std::mem::size_of::<Option<Sender<bool>>>() );
-    // 	dbg!( std::mem::size_of::<Events<bool>>() );
-    // }
+        impl ArcWake for Count {
+            fn wake_by_ref(arc_self: &Arc<Self>) {
+                arc_self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
 
-    // verify storage_len and num_observers
-    //
-    #[test]
-    //
-    fn new() {
-        let ph = Pharos::<bool>::new(5);
+        let count = Arc::new(Count(AtomicUsize::new(0)));
+        let waker = waker(count.clone());
+        let cx = &mut Context::from_waker(&waker);
 
-        assert_eq!(ph.observers.capacity(), 5);
+        let mut ph = Pharos::default();
+        let mut a = ph.observe(ObserveConfig::default()).expect("observe");
+        let mut b = ph.observe(Channel::Bounded(4).into()).expect("observe");
+
+        assert_eq!(Poll::Pending, Pin::new(&mut a).poll_next(cx));
+        assert_eq!(Poll::Pending, Pin::new(&mut b).poll_next(cx));
+
+        assert_eq!(2, ph.broadcast(1).expect("broadcast").delivered);
+        assert_eq!(2, count.0.load(Ordering::SeqCst));
+
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut a).poll_next(cx));
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut b).poll_next(cx));
     }
 
-    // verify storage_len and num_observers
+    // After n events, poll_ready yields once and wakes the task to continue.
     //
     #[test]
     //
-    fn storage_len() {
-        let mut ph = Pharos::<bool>::default();
+    fn yield_after() {
+        use futures::task::{waker, ArcWake};
 
-        assert_eq!(ph.storage_len(), 0);
-        assert_eq!(ph.num_observers(), 0);
-        assert_eq!(ph.free_slots.len(), 0);
+        struct Count(AtomicUsize);
 
-        let mut a = ph.observe(ObserveConfig::default()).expect("observe");
+        impl ArcWake for Count {
+            fn wake_by_ref(arc_self: &Arc<Self>) {
+                arc_self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
 
-        assert_eq!(ph.storage_len(), 1);
-        assert_eq!(ph.num_observers(), 1);
-        assert_eq!(ph.free_slots.len(), 0);
+        let count = Arc::new(Count(AtomicUsize::new(0)));
+        let waker = waker(count.clone());
+        let cx = &mut Context::from_waker(&waker);
 
-        let b = ph.observe(ObserveConfig::default()).expect("observe");
+        let mut ph = Pharos::default();
+        let events = ph.observe(ObserveConfig::default()).expect("observe");
 
-        assert_eq!(ph.storage_len(), 2);
-        assert_eq!(ph.num_observers(), 2);
-        assert_eq!(ph.free_slots.len(), 0);
+        ph.set_yield_after(Some(2));
 
-        a.close();
+        let mut pending = 0;
 
-        assert_eq!(ph.storage_len(), 2);
-        assert_eq!(ph.num_observers(), 1);
-        assert_eq!(&ph.free_slots, &[0]);
+        for i in 0..6 {
+            while Pin::new(&mut ph).poll_ready(cx).is_pending() {
+                pending += 1;
+            }
 
-        drop(b);
+            Pin::new(&mut ph).start_send(i).expect("start_send");
+        }
 
-        assert_eq!(ph.storage_len(), 2);
-        assert_eq!(ph.num_observers(), 0);
-        assert_eq!(&ph.free_slots, &[0, 1]);
+        // Before the 3rd and the 5th event.
+        //
+        assert_eq!(2, pending);
+        assert_eq!(2, count.0.load(Ordering::SeqCst));
+
+        block_on(ph.close()).expect("close");
+
+        assert_eq!(
+            (0..6).collect::<Vec<_>>(),
+            block_on(events.collect::<Vec<_>>())
+        );
     }
 
-    // observe: Make sure we are reusing slots
+    // Each observer gets the events as changed by its own transform, after its filter.
     //
     #[test]
     //
-    fn reuse() {
-        let mut ph = Pharos::<bool>::default();
-        let _a = ph.observe(ObserveConfig::default());
-        let b = ph.observe(ObserveConfig::default());
-        let _c = ph.observe(ObserveConfig::default());
+    fn observer_transform() {
+        #[derive(Clone, Debug, PartialEq)]
+        //
+        struct Login {
+            user: &'static str,
+            password: &'static str,
+        }
 
-        assert_eq!(ph.storage_len(), 3);
-        assert_eq!(ph.num_observers(), 3);
+        let mut ph = Pharos::default();
 
-        drop(b);
+        let plain = ph
+            .observe(ObserveConfig::default().transform(|evt| evt))
+            .expect("observe");
 
-        // It's important we call num_observers here, to clear the dropped one
-        //
-        assert_eq!(ph.storage_len(), 3);
-        assert_eq!(ph.num_observers(), 2);
+        let redacted = ph
+            .observe(
+                ObserveConfig::default()
+                    .filter(|evt: &Login| !evt.password.is_empty())
+                    .transform(|evt| Login {
+                        password: "***",
+                        ..evt
+                    }),
+            )
+            .expect("observe");
 
-        assert!(ph.observers[1].is_none());
-        assert_eq!(&ph.free_slots, &[1]);
+        let alice = Login {
+            user: "alice",
+            password: "secret",
+        };
 
-        let _d = ph.observe(ObserveConfig::default());
+        let bob = Login {
+            user: "bob",
+            password: "",
+        };
 
-        assert_eq!(ph.storage_len(), 3);
-        assert_eq!(ph.num_observers(), 3);
-        assert_eq!(ph.free_slots.len(), 0);
+        block_on(async {
+            ph.send(alice.clone()).await.expect("send");
+            ph.send(bob.clone()).await.expect("send");
+            ph.close().await.expect("close");
 
-        let _e = ph.observe(ObserveConfig::default());
+            assert_eq!(vec![alice, bob], plain.collect::<Vec<_>>().await);
 
-        // Now we should have pushed again
-        //
-        assert_eq!(ph.storage_len(), 4);
-        assert_eq!(ph.num_observers(), 4);
-        assert_eq!(ph.free_slots.len(), 0);
+            assert_eq!(
+                vec![Login {
+                    user: "alice",
+                    password: "***"
+                }],
+                redacted.collect::<Vec<_>>().await
+            );
+        });
     }
 
-    // observe: verify we can no longer observe after calling close
+    // New observers get the last event first, unless they filter it out or opt out.
     //
     #[test]
     //
-    fn observe_after_close() {
-        let mut ph = Pharos::<bool>::default();
+    fn cache_last() {
+        let mut ph = Pharos::default();
 
-        block_on(ph.close()).expect("close");
+        ph.set_cache_last(true);
 
-        let res = ph.observe(ObserveConfig::default());
+        let early = ph.observe(ObserveConfig::default()).expect("observe");
 
-        assert!(res.is_err());
-        assert_eq!(ErrorKind::Closed, res.unwrap_err().kind());
+        block_on(async {
+            ph.send(7).await.expect("send");
+            ph.send(42).await.expect("send");
+
+            let late = ph.observe(ObserveConfig::default()).expect("observe");
+            let small = ph
+                .observe(ObserveConfig::default().filter(|evt| *evt < 10))
+                .expect("observe");
+            let fresh = ph
+                .observe(ObserveConfig::default().no_replay())
+                .expect("observe");
+
+            ph.send(1).await.expect("send");
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![7, 42, 1], early.collect::<Vec<_>>().await);
+            assert_eq!(vec![42, 1], late.collect::<Vec<_>>().await);
+            assert_eq!(vec![1], small.collect::<Vec<_>>().await);
+            assert_eq!(vec![1], fresh.collect::<Vec<_>>().await);
+        });
     }
 
-    // observe: refuse Channel::Bounded(0)
+    // A history that doesn't fit in a bounded channel is cut short, and the observer sees the gap.
     //
     #[test]
     //
-    fn observe_refuse_zero() {
-        let mut ph = Pharos::<bool>::default();
+    fn replay_overflow() {
+        let mut ph = Pharos::default();
 
-        let res = ph.observe(Channel::Bounded(0).into());
+        ph.set_history_len(5);
+        ph.set_replay(true);
 
-        assert!(res.is_err());
-        assert_eq!(ErrorKind::MinChannelSizeOne, res.unwrap_err().kind());
+        block_on(async {
+            for i in 0..5 {
+                ph.send(i).await.expect("send");
+            }
+        });
+
+        let mut small = ph.observe(Channel::Bounded(1).into()).expect("observe");
+        let mut replayed = Vec::new();
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        while let Poll::Ready(Some(evt)) = Pin::new(&mut small).poll_next(cx) {
+            replayed.push(evt);
+        }
+
+        assert!(replayed.len() < 5);
+        assert_eq!((0..replayed.len()).collect::<Vec<_>>(), replayed);
+
+        block_on(async {
+            ph.send(5).await.expect("send");
+
+            assert_eq!(Some(5), small.next().await);
+            assert_eq!(Some((replayed.len() as u64, 5)), small.last_gap());
+        });
     }
 
-    // verify that one observer blocks pharos.
+    // Subscribing while another thread sends: every observer gets each event once, either from the replay
+    // or live, without duplicates or gaps at the handoff. Both halves lock the same pharos, so an observer
+    // is added either before or after an event is recorded and sent out.
     //
     #[test]
     //
-    fn poll_ready_pending() {
-        block_on(poll_fn(move |mut cx| {
-            let mut ph = Pharos::default();
+    fn replay_handoff() {
+        const EVENTS: usize = 1000;
 
-            let _open = ph.observe(Channel::Bounded(10).into()).expect("observe");
-            let mut full = ph.observe(Channel::Bounded(1).into()).expect("observe");
-            let _unbound = ph.observe(Channel::Unbounded.into()).expect("observe");
+        let mut ph = Pharos::default();
 
-            let mut ph = Pin::new(&mut ph);
+        ph.set_history_len(EVENTS);
+        ph.set_replay(true);
 
-            assert_matches!(ph.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(_)));
-            assert!(ph.as_mut().start_send(true).is_ok());
+        let (mut producer, mut registrar) = ph.split();
 
-            assert_matches!(ph.as_mut().poll_ready(&mut cx), Poll::Pending);
+        let sending = std::thread::spawn(move || {
+            for i in 0..EVENTS {
+                producer.broadcast(i).expect("broadcast");
+            }
 
-            assert_eq!(Pin::new(&mut full).poll_next(cx), Poll::Ready(Some(true)));
+            block_on(producer.close()).expect("close");
+        });
 
-            assert_matches!(ph.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(_)));
+        let mut observers = Vec::new();
 
-            ().into()
-        }));
+        while observers.len() < 100 {
+            match registrar.observe(Channel::Unbounded.into()) {
+                Ok(events) => observers.push(events),
+
+                // The producer is done.
+                //
+                Err(e) if e.kind() == ErrorKind::Closed => break,
+                Err(e) => panic!("observe: {}", e),
+            }
+
+            std::thread::yield_now();
+        }
+
+        sending.join().expect("join");
+
+        block_on(async {
+            for events in observers {
+                assert_eq!(
+                    (0..EVENTS).collect::<Vec<_>>(),
+                    events.collect::<Vec<_>>().await
+                );
+            }
+        });
     }
 
-    // pharos drops closed observers.
+    // Keyed events only go to the observers registered under that key.
     //
     #[test]
     //
-    fn poll_ready_drop() {
-        block_on(poll_fn(move |mut cx| {
-            let mut ph = Pharos::<bool>::default();
+    fn notify_keyed() {
+        let mut ph = Pharos::default();
 
-            let _open = ph.observe(Channel::Bounded(10).into()).expect("observe");
-            let full = ph.observe(Channel::Bounded(1).into()).expect("observe");
-            let _unbound = ph.observe(Channel::Unbounded.into()).expect("observe");
+        let one_a = ph
+            .observe(ObserveConfig::default().key(1_u32))
+            .expect("observe");
+        let one_b = ph
+            .observe(ObserveConfig::default().key(1_u32))
+            .expect("observe");
+        let gone = ph
+            .observe(ObserveConfig::default().key(1_u32))
+            .expect("observe");
+        let two = ph
+            .observe(ObserveConfig::default().key(2_u32))
+            .expect("observe");
+        let other_type = ph
+            .observe(ObserveConfig::default().key(1_u64))
+            .expect("observe");
+        let all = ph.observe(ObserveConfig::default()).expect("observe");
 
-            let mut ph = Pin::new(&mut ph);
+        // Reuse the slot of an observer under key 1 for one under key 2.
+        //
+        drop(gone);
+        assert_eq!(5, ph.num_observers());
+        let reused = ph
+            .observe(ObserveConfig::default().key(2_u32))
+            .expect("observe");
 
-            drop(full);
+        block_on(async {
+            ph.notify_keyed(1_u32, 1).await.expect("notify");
+            ph.notify_keyed(2_u32, 2).await.expect("notify");
+            ph.notify_keyed(3_u32, 3).await.expect("notify");
+            ph.send(4).await.expect("send");
+            ph.close().await.expect("close");
 
-            assert_matches!(ph.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(_)));
+            assert_eq!(vec![1, 4], one_a.collect::<Vec<_>>().await);
+            assert_eq!(vec![1, 4], one_b.collect::<Vec<_>>().await);
+            assert_eq!(vec![2, 4], two.collect::<Vec<_>>().await);
+            assert_eq!(vec![2, 4], reused.collect::<Vec<_>>().await);
+            assert_eq!(vec![4], other_type.collect::<Vec<_>>().await);
+            assert_eq!(vec![4], all.collect::<Vec<_>>().await);
 
-            assert!(ph.observers[1].is_none());
-            ().into()
-        }));
+            assert_matches!(
+                ph.notify_keyed(1_u32, 5).await.unwrap_err().kind(),
+                ErrorKind::Closed
+            );
+        });
     }
 
-    // poll_ready should return closed if the pharos is closed.
+    // notify_acked only resolves once the observer has taken the event out of the channel.
     //
     #[test]
     //
-    fn poll_ready_closed() {
-        block_on(poll_fn(move |mut cx| {
-            let mut ph = Pharos::<bool>::default();
-
-            let mut ph = Pin::new(&mut ph);
+    fn notify_acked() {
+        block_on(poll_fn(move |cx| {
+            let mut ph = Pharos::default();
 
-            assert_matches!(ph.as_mut().poll_close(cx), Poll::Ready(Ok(())));
+            let mut evts = ph.observe(Channel::Bounded(2).into()).expect("observe");
+            let mut ack = Box::pin(ph.notify_acked(3));
 
-            let res = ph.as_mut().poll_ready(&mut cx);
+            assert_matches!(ack.as_mut().poll(cx), Poll::Pending);
+            assert_matches!(ack.as_mut().poll(cx), Poll::Pending);
 
-            assert_matches!(res, Poll::Ready(Err(_)));
+            assert_eq!(Pin::new(&mut evts).poll_next(cx), Poll::Ready(Some(3)));
 
-            match res {
-                Poll::Ready(Err(e)) => assert_eq!(ErrorKind::Closed, e.kind()),
-                _ => assert!(false, "wrong result "),
-            }
+            assert_matches!(ack.as_mut().poll(cx), Poll::Ready(Ok(1)));
 
             ().into()
         }));
     }
 
-    // start_send verify message arrives.
+    // notify_acked_timeout stops waiting for an observer that doesn't read, and names it.
     //
     #[test]
     //
-    fn start_send_arrive() {
-        block_on(poll_fn(move |mut cx| {
-            let mut ph = Pharos::default();
+    fn notify_acked_timeout() {
+        let timer = timer::MockTimer::new();
+        let cx = &mut Context::from_waker(noop_waker_ref());
+        let mut ph = Pharos::default();
 
-            let _open = ph.observe(Channel::Bounded(10).into()).expect("observe");
-            let mut full = ph.observe(Channel::Bounded(1).into()).expect("observe");
-            let _unbound = ph.observe(Channel::Unbounded.into()).expect("observe");
+        let mut reader = ph.observe(Channel::Bounded(2).into()).expect("observe");
+        let stalled = ph.observe(Channel::Bounded(2).into()).expect("observe");
 
-            let mut ph = Pin::new(&mut ph);
+        ph.set_timer(None);
 
-            assert_matches!(ph.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(_)));
-            assert!(ph.as_mut().start_send(3).is_ok());
+        assert_eq!(
+            ErrorKind::NoTimer,
+            block_on(ph.notify_acked_timeout(1, Duration::from_secs(1)))
+                .unwrap_err()
+                .kind()
+        );
 
-            assert_eq!(Pin::new(&mut full).poll_next(cx), Poll::Ready(Some(3)));
+        ph.set_timer(Some(Arc::new(timer.clone())));
 
-            ().into()
-        }));
+        let mut ack = Box::pin(ph.notify_acked_timeout(2, Duration::from_secs(1)));
+
+        assert_matches!(ack.as_mut().poll(cx), Poll::Pending);
+        assert_eq!(Pin::new(&mut reader).poll_next(cx), Poll::Ready(Some(2)));
+        assert_matches!(ack.as_mut().poll(cx), Poll::Pending);
+
+        timer.advance(Duration::from_secs(1));
+
+        match ack.as_mut().poll(cx) {
+            Poll::Ready(Err(e)) => {
+                let failed = e.observers();
+
+                assert_eq!(1, failed.len());
+                assert_eq!(stalled.id(), failed[0].0);
+                assert_eq!(ErrorKind::Timeout, failed[0].1.kind());
+            }
+
+            other => panic!("wrong result: {:?}", other),
+        }
     }
 
     // pharos drops closed observers.
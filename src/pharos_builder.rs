@@ -0,0 +1,167 @@
+use crate::{import::*, Channel, Filter, Pharos};
+
+/// Configure a [Pharos] in one place instead of calling its setters after creating it. Returned by
+/// [Pharos::builder].
+///
+/// ```
+/// use pharos::*;
+///
+/// let pharos: Pharos<usize> = Pharos::builder()
+///
+///    .channel      ( Channel::Bounded( 5 ) )
+///    .max_observers( 100                   )
+///    .build()
+/// ;
+/// ```
+//
+pub struct PharosBuilder<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    capacity: usize,
+    channel: Channel,
+    filter: Option<Filter<Event>>,
+    max_observers: Option<usize>,
+    auto_compact: Option<f32>,
+}
+
+impl<Event> Default for PharosBuilder<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    fn default() -> Self {
+        Self {
+            capacity: 10,
+            channel: Channel::default(),
+            filter: None,
+            max_observers: None,
+            auto_compact: None,
+        }
+    }
+}
+
+impl<Event> PharosBuilder<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    /// The initial capacity of the storage for observers. See [Pharos::new].
+    //
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// The channel for observers that don't choose one. See [Pharos::set_default_channel].
+    //
+    pub fn channel(mut self, channel: Channel) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Only send out the events that pass this filter. See [Pharos::set_filter].
+    //
+    pub fn filter(mut self, filter: Filter<Event>) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Limit the number of observers. See [Pharos::set_max_observers].
+    //
+    pub fn max_observers(mut self, max: usize) -> Self {
+        self.max_observers = Some(max);
+        self
+    }
+
+    /// Compact the storage once more than `ratio` of it is free. See [Pharos::set_auto_compact].
+    //
+    pub fn auto_compact(mut self, ratio: f32) -> Self {
+        self.auto_compact = Some(ratio);
+        self
+    }
+
+    /// Create the pharos.
+    //
+    pub fn build(self) -> Pharos<Event> {
+        let mut pharos = Pharos::new(self.capacity);
+
+        pharos.set_default_channel(self.channel);
+        pharos.set_filter(self.filter);
+        pharos.set_auto_compact(self.auto_compact);
+
+        if let Some(max) = self.max_observers {
+            pharos.set_max_observers(max);
+        }
+
+        pharos
+    }
+}
+
+impl<Event> fmt::Debug for PharosBuilder<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::PharosBuilder<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Observers get the default channel unless they choose one, and the limit on observers holds.
+    //
+    #[test]
+    //
+    fn build() {
+        let mut ph = Pharos::builder()
+            .channel(Channel::Bounded(1))
+            .max_observers(2)
+            .build();
+
+        let bounded = ph.observe(ObserveConfig::default()).expect("observe");
+        let unbounded = ph.observe(Channel::Unbounded.into()).expect("observe");
+
+        let res = ph.observe(ObserveConfig::default());
+        assert_eq!(ErrorKind::TooManyObservers, res.unwrap_err().kind());
+
+        block_on(async {
+            ph.send(1).await.expect("send");
+
+            assert_eq!(
+                Ok(SendOutcome::WouldBlock(1)),
+                ph.send_if_ready(2).map_err(|_| ())
+            );
+
+            drop(bounded);
+
+            ph.send(2).await.expect("send");
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![1, 2], unbounded.collect::<Vec<_>>().await);
+        });
+    }
+
+    // Events rejected by the filter of the pharos reach no observer.
+    //
+    #[test]
+    //
+    fn filter() {
+        let mut ph = Pharos::builder()
+            .filter(Filter::Pointer(|evt: &usize| *evt > 2))
+            .build();
+
+        let events = ph.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            for i in 1..=4 {
+                ph.send(i).await.expect("send");
+            }
+
+            ph.close().await.expect("close");
+
+            assert_eq!(vec![3, 4], events.collect::<Vec<_>>().await);
+        });
+    }
+}
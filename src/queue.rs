@@ -0,0 +1,199 @@
+use crate::{import::*, wake_batch::WakeBatch};
+
+/// Create the queue behind the channels pharos implements itself, the ring buffer and the tiered
+/// channel. It keeps the items, the close flags and the wakers. The channels only decide what happens
+/// to an item that doesn't fit, see [QueueSender::push].
+//
+pub(crate) fn queue<T>(capacity: usize) -> (QueueSender<T>, QueueReceiver<T>) {
+    let shared = Arc::new(Shared {
+        inner: Mutex::new(Inner {
+            items: VecDeque::with_capacity(capacity),
+            tx_closed: false,
+            rx_closed: false,
+        }),
+        rx_waker: AtomicWaker::new(),
+        tx_waker: AtomicWaker::new(),
+    });
+
+    (
+        QueueSender {
+            shared: shared.clone(),
+        },
+        QueueReceiver { shared },
+    )
+}
+
+struct Shared<T> {
+    inner: Mutex<Inner<T>>,
+    rx_waker: AtomicWaker,
+    tx_waker: AtomicWaker,
+}
+
+struct Inner<T> {
+    items: VecDeque<T>,
+    tx_closed: bool,
+    rx_closed: bool,
+}
+
+impl<T> Shared<T> {
+    // Nothing panics while holding the lock, but don't make things worse if it did.
+    //
+    fn lock(&self) -> MutexGuard<'_, Inner<T>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+pub(crate) struct QueueSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> QueueSender<T> {
+    /// Queue an item with `admit`, which gets the queued items and decides whether and how the new
+    /// one goes in. It hands the item back if it doesn't. Also hands the item back if the receiver is
+    /// closed. With a `batch`, waking the receiver is left to the batch.
+    //
+    pub(crate) fn push<R>(
+        &self,
+        item: T,
+        batch: Option<&mut WakeBatch>,
+        admit: impl FnOnce(&mut VecDeque<T>, T) -> Result<R, T>,
+    ) -> Result<R, T> {
+        let admitted = {
+            let mut inner = self.shared.lock();
+
+            if inner.rx_closed {
+                return Err(item);
+            }
+
+            admit(&mut inner.items, item)?
+        };
+
+        match batch {
+            Some(batch) => {
+                if let Some(waker) = self.shared.rx_waker.take() {
+                    batch.defer(waker);
+                }
+            }
+
+            None => self.shared.rx_waker.wake(),
+        }
+
+        Ok(admitted)
+    }
+
+    /// Ready once `has_room` is true for the queued items. The receiver wakes us when it takes an item.
+    //
+    pub(crate) fn poll_room(
+        &self,
+        cx: &mut Context<'_>,
+        has_room: impl Fn(&VecDeque<T>) -> bool,
+    ) -> Poll<()> {
+        if has_room(&self.shared.lock().items) {
+            return Poll::Ready(());
+        }
+
+        // Register and check again, so we don't miss the receiver making room in between.
+        //
+        self.shared.tx_waker.register(cx.waker());
+
+        if has_room(&self.shared.lock().items) {
+            return Poll::Ready(());
+        }
+
+        Poll::Pending
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.shared.lock().rx_closed
+    }
+
+    /// Let the receiver know no more items will come.
+    //
+    pub(crate) fn close(&self) {
+        self.shared.lock().tx_closed = true;
+        self.shared.rx_waker.wake();
+    }
+}
+
+impl<T> Drop for QueueSender<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+pub(crate) struct QueueReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> QueueReceiver<T> {
+    /// Stop accepting new items. Items already queued can still be read.
+    //
+    pub(crate) fn close(&mut self) {
+        self.shared.lock().rx_closed = true;
+        self.shared.tx_waker.wake();
+    }
+}
+
+// Otherwise the sender never learns that nobody is listening anymore.
+//
+impl<T> Drop for QueueReceiver<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl<T> Stream for QueueReceiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Register first, so we don't miss a wake up between checking and returning pending.
+        //
+        self.shared.rx_waker.register(cx.waker());
+
+        let mut inner = self.shared.lock();
+
+        if let Some(item) = inner.items.pop_front() {
+            drop(inner);
+            self.shared.tx_waker.wake();
+
+            return Poll::Ready(Some(item));
+        }
+
+        if inner.tx_closed || inner.rx_closed {
+            return Poll::Ready(None);
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use super::*;
+
+    // Items the admission refuses are handed back, and the stream ends when the sender goes away.
+    //
+    #[test]
+    //
+    fn admit() {
+        let (tx, rx) = queue(2);
+
+        let below_three = |items: &mut VecDeque<usize>, item| {
+            if item < 3 {
+                items.push_back(item);
+                Ok(())
+            } else {
+                Err(item)
+            }
+        };
+
+        assert_eq!(Ok(()), tx.push(1, None, below_three));
+        assert_eq!(Err(3), tx.push(3, None, below_three));
+        assert_eq!(Ok(()), tx.push(2, None, below_three));
+
+        drop(tx);
+
+        assert_eq!(vec![1, 2], block_on(rx.collect::<Vec<usize>>()));
+    }
+}
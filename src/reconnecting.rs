@@ -0,0 +1,319 @@
+use crate::{import::*, Error, ErrorKind, Events, Observable, ObserveConfig, Timer};
+
+/// How [reconnecting_observe] retries when a subscription ends or the observable refuses new observers
+/// because it is closed.
+//
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+//
+pub struct RetryPolicy {
+    /// How long to wait before trying to subscribe again.
+    //
+    pub delay: Duration,
+
+    /// How many times in a row to retry without receiving an event before giving up. `None` retries
+    /// for as long as the observable exists.
+    //
+    pub max_retries: Option<usize>,
+}
+
+impl RetryPolicy {
+    /// Create a new retry policy.
+    //
+    pub fn new(delay: Duration, max_retries: Option<usize>) -> Self {
+        Self { delay, max_retries }
+    }
+}
+
+/// Observe a shared observable and subscribe again whenever the stream ends because the observable
+/// was closed, eg. a [Pharos](crate::Pharos) that gets [reopened](crate::Pharos::reopen). `config` is called
+/// for every subscription, since an [ObserveConfig] can only be used once.
+///
+/// A close is considered transient for as long as the observable is alive. When a subscription ends, and while
+/// the observable refuses new observers with [ErrorKind::Closed], we retry according to `policy`, using `timer`
+/// to wait in between. Every retry counts as an attempt until an event comes in, so an observable that keeps
+/// handing out streams that end right away, eg. because it is [over its limit](crate::OverLimitBehavior::Graceful),
+/// doesn't keep us busy. The stream ends for good when the observable is dropped, when the retries run out or
+/// when observing fails with another error.
+///
+/// Only a weak reference to the observable is kept, so this doesn't keep it alive.
+//
+pub fn reconnecting_observe<Event, O, C>(
+    handle: &Arc<Mutex<O>>,
+    config: C,
+    policy: RetryPolicy,
+    timer: Arc<dyn Timer>,
+) -> Reconnecting<Event, O, C>
+where
+    Event: 'static + Clone + Sync + Send,
+    O: Observable<Event>,
+    O::Error: Into<Error>,
+    C: FnMut() -> ObserveConfig<Event>,
+{
+    Reconnecting {
+        handle: Arc::downgrade(handle),
+        config,
+        policy,
+        timer,
+        retries: 0,
+        state: ReconnectState::Connect,
+    }
+}
+
+/// Stream returned by [reconnecting_observe].
+//
+pub struct Reconnecting<Event, O, C>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    handle: Weak<Mutex<O>>,
+    config: C,
+    policy: RetryPolicy,
+    timer: Arc<dyn Timer>,
+    retries: usize,
+    state: ReconnectState<Event>,
+}
+
+enum ReconnectState<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    Connect,
+    Active(Events<Event>),
+    Waiting(Pin<Box<dyn Future<Output = ()> + Send>>),
+    Done,
+}
+
+impl<Event, O, C> Reconnecting<Event, O, C>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    /// Stop observing and don't reconnect. Events that were already queued can still be read.
+    //
+    pub fn close(&mut self) {
+        if let ReconnectState::Active(events) = &mut self.state {
+            events.close();
+        } else {
+            self.state = ReconnectState::Done;
+        }
+
+        self.handle = Weak::new();
+    }
+
+    /// Whether the stream has ended for good or [Reconnecting::close] was called.
+    //
+    pub fn is_closed(&self) -> bool {
+        match &self.state {
+            ReconnectState::Done => true,
+            ReconnectState::Active(events) => events.is_closed() && self.handle.strong_count() == 0,
+            _ => false,
+        }
+    }
+}
+
+impl<Event, O, C> Reconnecting<Event, O, C>
+where
+    Event: 'static + Clone + Sync + Send,
+    O: Observable<Event>,
+    O::Error: Into<Error>,
+    C: FnMut() -> ObserveConfig<Event>,
+{
+    // Try to subscribe once and decide what to do next.
+    //
+    fn connect(&mut self) -> ReconnectState<Event> {
+        let handle = match self.handle.upgrade() {
+            Some(handle) => handle,
+            None => return ReconnectState::Done,
+        };
+
+        let res = handle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .observe((self.config)())
+            .map_err(Into::into);
+
+        match res {
+            Ok(events) => ReconnectState::Active(events),
+            Err(e) if e.kind() == ErrorKind::Closed => self.retry(),
+            Err(_) => ReconnectState::Done,
+        }
+    }
+
+    // Wait before subscribing again, unless the retries ran out or the observable is gone.
+    //
+    fn retry(&mut self) -> ReconnectState<Event> {
+        if self.handle.strong_count() == 0 {
+            return ReconnectState::Done;
+        }
+
+        match self.policy.max_retries {
+            Some(max) if self.retries >= max => ReconnectState::Done,
+
+            _ => {
+                self.retries += 1;
+                ReconnectState::Waiting(self.timer.sleep(self.policy.delay))
+            }
+        }
+    }
+}
+
+// We never pin project, the closure is just called through a mutable reference.
+//
+impl<Event, O, C> Unpin for Reconnecting<Event, O, C> where Event: 'static + Clone + Sync + Send {}
+
+impl<Event, O, C> Stream for Reconnecting<Event, O, C>
+where
+    Event: 'static + Clone + Sync + Send,
+    O: Observable<Event>,
+    O::Error: Into<Error>,
+    C: FnMut() -> ObserveConfig<Event>,
+{
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            this.state = match &mut this.state {
+                ReconnectState::Done => return Poll::Ready(None),
+
+                ReconnectState::Active(events) => match ready!(Pin::new(events).poll_next(cx)) {
+                    Some(evt) => {
+                        this.retries = 0;
+                        return Poll::Ready(Some(evt));
+                    }
+
+                    None => this.retry(),
+                },
+
+                ReconnectState::Waiting(sleep) => {
+                    ready!(sleep.as_mut().poll(cx));
+                    ReconnectState::Connect
+                }
+
+                ReconnectState::Connect => this.connect(),
+            };
+        }
+    }
+}
+
+impl<Event, O, C> fmt::Debug for Reconnecting<Event, O, C>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::Reconnecting<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+    use futures::task::noop_waker;
+
+    // Keep receiving events after the pharos was closed and reopened, and stop once it's gone.
+    //
+    #[test]
+    //
+    fn reconnect() {
+        let timer = timer::MockTimer::new();
+        let shared = Arc::new(Mutex::new(Pharos::default()));
+
+        let send = |evt| {
+            let mut ph = shared.lock().expect("lock");
+            block_on(ph.send(evt)).expect("send");
+        };
+
+        let mut events = reconnecting_observe(
+            &shared,
+            ObserveConfig::default,
+            RetryPolicy::new(Duration::from_secs(1), None),
+            Arc::new(timer.clone()),
+        );
+
+        let waker = noop_waker();
+        let cx = &mut Context::from_waker(&waker);
+
+        assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Pending);
+
+        send(1);
+        block_on(shared.lock().expect("lock").close()).expect("close");
+
+        assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Ready(Some(1)));
+
+        // Closed, so we wait for the timer to try again.
+        //
+        assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Pending);
+        timer.advance(Duration::from_secs(1));
+        assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Pending);
+
+        shared.lock().expect("lock").reopen();
+        timer.advance(Duration::from_secs(1));
+        assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Pending);
+
+        send(2);
+        assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Ready(Some(2)));
+
+        drop(shared);
+
+        assert_eq!(block_on(events.next()), None);
+        assert!(events.is_closed());
+    }
+
+    // Give up once the retries run out.
+    //
+    #[test]
+    //
+    fn max_retries() {
+        let timer = timer::MockTimer::new();
+        let shared = Arc::new(Mutex::new(Pharos::<usize>::default()));
+
+        block_on(shared.lock().expect("lock").close()).expect("close");
+
+        let mut events = reconnecting_observe(
+            &shared,
+            ObserveConfig::default,
+            RetryPolicy::new(Duration::from_secs(1), Some(1)),
+            Arc::new(timer.clone()),
+        );
+
+        block_on(poll_fn(|cx| {
+            assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Pending);
+            timer.advance(Duration::from_secs(1));
+            assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Ready(None));
+
+            ().into()
+        }));
+    }
+
+    // A subscription that ends right away doesn't make us subscribe again without waiting.
+    //
+    #[test]
+    //
+    fn ended_right_away() {
+        let timer = timer::MockTimer::new();
+        let shared = Arc::new(Mutex::new(Pharos::<usize>::default()));
+
+        {
+            let mut ph = shared.lock().expect("lock");
+
+            ph.set_max_observers(0);
+            ph.set_over_limit_behavior(OverLimitBehavior::Graceful);
+        }
+
+        let mut events = reconnecting_observe(
+            &shared,
+            ObserveConfig::default,
+            RetryPolicy::new(Duration::from_secs(1), Some(2)),
+            Arc::new(timer.clone()),
+        );
+
+        let cx = &mut Context::from_waker(noop_waker_ref());
+
+        assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Pending);
+        timer.advance(Duration::from_secs(1));
+        assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Pending);
+        timer.advance(Duration::from_secs(1));
+        assert_eq!(Pin::new(&mut events).poll_next(cx), Poll::Ready(None));
+    }
+}
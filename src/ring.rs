@@ -0,0 +1,99 @@
+use crate::{
+    import::*,
+    queue::{queue, QueueReceiver, QueueSender},
+    wake_batch::WakeBatch,
+};
+
+/// Create a channel that holds at most `cap` items. When full, sending drops the oldest item
+/// instead of waiting for the receiver. Used for [Channel::RingBuffer](crate::Channel::RingBuffer).
+//
+pub(crate) fn ring<T>(cap: usize) -> (RingSender<T>, RingReceiver<T>) {
+    let (tx, rx) = queue(cap);
+
+    (RingSender { tx, cap }, rx)
+}
+
+pub(crate) type RingReceiver<T> = QueueReceiver<T>;
+
+pub(crate) struct RingSender<T> {
+    tx: QueueSender<T>,
+    cap: usize,
+}
+
+impl<T> RingSender<T> {
+    /// Queue an item. If the ring is full, the oldest item is removed and returned. Hands
+    /// the item back if the receiver is closed.
+    //
+    pub(crate) fn send(&self, item: T) -> Result<Option<T>, T> {
+        self.tx
+            .push(item, None, |items, item| self.evict(items, item))
+    }
+
+    /// Like [RingSender::send], but leaves waking the receiver to the batch.
+    //
+    pub(crate) fn send_deferred(&self, item: T, batch: &mut WakeBatch) -> Result<Option<T>, T> {
+        self.tx
+            .push(item, Some(batch), |items, item| self.evict(items, item))
+    }
+
+    // A ring always takes the item, making room by dropping the oldest one.
+    //
+    fn evict(&self, items: &mut VecDeque<T>, item: T) -> Result<Option<T>, T> {
+        let evicted = if items.len() == self.cap {
+            items.pop_front()
+        } else {
+            None
+        };
+
+        items.push_back(item);
+
+        Ok(evicted)
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// Let the receiver know no more items will come.
+    //
+    pub(crate) fn close(&self) {
+        self.tx.close();
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use super::*;
+
+    // The oldest items are dropped on overflow and the stream ends when the sender goes away.
+    //
+    #[test]
+    //
+    fn overflow() {
+        let (tx, rx) = ring(2);
+
+        assert_eq!(tx.send(1), Ok(None));
+        assert_eq!(tx.send(2), Ok(None));
+        assert_eq!(tx.send(3), Ok(Some(1)));
+        assert_eq!(tx.send(4), Ok(Some(2)));
+
+        drop(tx);
+
+        assert_eq!(vec![3, 4], block_on(rx.collect::<Vec<usize>>()));
+    }
+
+    // The sender notices when the receiver goes away.
+    //
+    #[test]
+    //
+    fn receiver_dropped() {
+        let (tx, rx) = ring::<usize>(2);
+
+        assert!(!tx.is_closed());
+
+        drop(rx);
+
+        assert!(tx.is_closed());
+    }
+}
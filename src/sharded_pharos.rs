@@ -0,0 +1,211 @@
+use crate::{import::*, pharos::lock, Error, Events, Observable, ObserveConfig, Pharos};
+
+/// Partitions observers over several [Pharos] shards, each behind its own lock, so that
+/// observing and notifying from different threads contend less than with a single
+/// `Mutex<Pharos>`. All methods take `&self`, so you can share it in an `Arc`.
+///
+/// New observers are assigned to shards round-robin. [ShardedPharos::notify] delivers an event
+/// to every shard, and shards that are ready get the event right away, even if observers in
+/// another shard are still full. Locks are never held across an await point.
+///
+/// **Note**: with bounded channels, a shard that is full only wakes the task that polled it
+/// last. Use a single task to notify if your observers can fill up their channels.
+//
+pub struct ShardedPharos<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    shards: Vec<Mutex<Pharos<Event>>>,
+    next: AtomicUsize,
+}
+
+impl<Event> ShardedPharos<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    /// Create a new sharded pharos with `shards` shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    //
+    pub fn new(shards: usize) -> Self {
+        assert!(shards > 0, "ShardedPharos: shards must be at least 1");
+
+        Self {
+            shards: (0..shards).map(|_| Mutex::new(Pharos::default())).collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of shards.
+    //
+    pub fn num_shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Observe this pharos. The observer is placed in the next shard, round-robin.
+    /// Works like [Observable::observe], but through a shared reference.
+    //
+    pub fn observe(&self, options: ObserveConfig<Event>) -> Result<Events<Event>, Error> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.shards.len();
+
+        self.shard(i).observe(options)
+    }
+
+    /// The number of observers still listening, summed over all shards. See [Pharos::num_observers].
+    //
+    pub fn num_observers(&self) -> usize {
+        (0..self.shards.len())
+            .map(|i| self.shard(i).num_observers())
+            .sum()
+    }
+
+    /// Send an event to the observers in all shards. Resolves once every shard has accepted the
+    /// event. Returns an error if any shard is closed.
+    //
+    pub async fn notify(&self, evt: Event) -> Result<(), Error> {
+        let mut done = vec![false; self.shards.len()];
+
+        poll_fn(|cx| {
+            let mut pending = false;
+
+            for (i, done) in done.iter_mut().enumerate().filter(|(_, done)| !**done) {
+                let mut shard = self.shard(i);
+
+                match Pin::new(&mut *shard).poll_ready(cx) {
+                    Poll::Ready(Ok(())) => {
+                        Pin::new(&mut *shard).start_send(evt.clone())?;
+                        *done = true;
+                    }
+
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Pending => pending = true,
+                }
+            }
+
+            if pending {
+                Poll::Pending
+            } else {
+                Poll::Ready(Ok(()))
+            }
+        })
+        .await
+    }
+
+    /// Close all shards. Observers will see the end of their streams.
+    //
+    pub async fn close(&self) -> Result<(), Error> {
+        let mut done = vec![false; self.shards.len()];
+
+        poll_fn(|cx| {
+            let mut pending = false;
+
+            for (i, done) in done.iter_mut().enumerate().filter(|(_, done)| !**done) {
+                match Pin::new(&mut *self.shard(i)).poll_close(cx) {
+                    Poll::Ready(res) => {
+                        res?;
+                        *done = true;
+                    }
+
+                    Poll::Pending => pending = true,
+                }
+            }
+
+            if pending {
+                Poll::Pending
+            } else {
+                Poll::Ready(Ok(()))
+            }
+        })
+        .await
+    }
+
+    fn shard(&self, i: usize) -> MutexGuard<'_, Pharos<Event>> {
+        lock(&self.shards[i])
+    }
+}
+
+impl<Event> Observable<Event> for ShardedPharos<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    type Error = Error;
+
+    fn observe(&mut self, options: ObserveConfig<Event>) -> Result<Events<Event>, Self::Error> {
+        ShardedPharos::observe(self, options)
+    }
+}
+
+impl<Event> fmt::Debug for ShardedPharos<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::ShardedPharos<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Events reach observers in every shard.
+    //
+    #[test]
+    //
+    fn all_shards() {
+        let ph = ShardedPharos::new(3);
+
+        let observers: Vec<_> = (0..5)
+            .map(|_| ph.observe(ObserveConfig::default()).expect("observe"))
+            .collect();
+
+        assert_eq!(ph.num_shards(), 3);
+        assert_eq!(ph.num_observers(), 5);
+
+        block_on(async {
+            ph.notify(1).await.expect("notify");
+            ph.notify(2).await.expect("notify");
+            ph.close().await.expect("close");
+        });
+
+        for obs in observers {
+            assert_eq!(vec![1, 2], block_on(obs.collect::<Vec<usize>>()));
+        }
+    }
+
+    // A full observer in one shard doesn't keep others from receiving events, and the shards
+    // can be observed from another thread while a notify is pending.
+    //
+    #[test]
+    //
+    fn shards_dont_block() {
+        let ph = Arc::new(ShardedPharos::new(2));
+
+        let _full = ph.observe(Channel::Bounded(1).into()).expect("observe");
+        let mut other = ph.observe(Channel::Bounded(1).into()).expect("observe");
+
+        block_on(ph.notify(1)).expect("notify");
+        assert_eq!(block_on(other.next()), Some(1));
+
+        let mut notify = Box::pin(ph.notify(2));
+
+        block_on(poll_fn(|cx| {
+            assert_matches!(notify.as_mut().poll(cx), Poll::Pending);
+            assert_eq!(Pin::new(&mut other).poll_next(cx), Poll::Ready(Some(2)));
+
+            ().into()
+        }));
+
+        let ph2 = ph.clone();
+
+        let _third = std::thread::spawn(move || ph2.observe(ObserveConfig::default()))
+            .join()
+            .expect("join")
+            .expect("observe");
+
+        assert_eq!(ph.num_observers(), 3);
+    }
+}
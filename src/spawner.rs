@@ -0,0 +1,45 @@
+use crate::import::*;
+
+/// Spawn tasks on an executor, so [Pharos::notify_parallel](crate::Pharos::notify_parallel) doesn't tie
+/// pharos to a specific runtime. Implement it by calling the spawn function of your executor.
+///
+/// ```
+/// use pharos::*;
+/// use std::{ future::Future, pin::Pin };
+///
+/// struct MySpawner;
+///
+/// impl Spawner for MySpawner
+/// {
+///    fn spawn( &self, task: Pin<Box< dyn Future<Output=()> + Send >> )
+///    {
+///       // Call the spawn function of your executor here, eg. `tokio::spawn( task )`.
+///       //
+///       drop( task );
+///    }
+/// }
+/// ```
+//
+pub trait Spawner {
+    /// Run `task` to completion in the background.
+    //
+    fn spawn(&self, task: Pin<Box<dyn Future<Output = ()> + Send>>);
+}
+
+/// What happened to the observers during [Pharos::notify_parallel](crate::Pharos::notify_parallel).
+//
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+//
+pub struct FanOutReport {
+    /// Observers that got the event.
+    //
+    pub delivered: usize,
+
+    /// Observers that were paused or filtered the event out.
+    //
+    pub skipped: usize,
+
+    /// Observers that were removed because they were closed, their filter panicked or their task was lost.
+    //
+    pub removed: usize,
+}
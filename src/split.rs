@@ -0,0 +1,187 @@
+use crate::{
+    import::*, pharos::lock, BroadcastReport, Error, Events, Observable, ObserveConfig, ObserverId,
+    Pharos,
+};
+
+/// The half of a [Pharos] that sends events, created with [Pharos::split]. It implements
+/// [Sink], so use [SinkExt::send](futures::SinkExt::send) and [SinkExt::close](futures::SinkExt::close)
+/// like on a pharos. It can't add or remove observers, that's up to the [Registrar].
+///
+/// The lock on the shared pharos is never held across an await point.
+//
+pub struct Producer<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    pharos: Arc<Mutex<Pharos<Event>>>,
+}
+
+impl<Event> Producer<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    /// Send an event right away. See [Pharos::broadcast].
+    //
+    pub fn broadcast(&mut self, evt: Event) -> Result<BroadcastReport, Error> {
+        lock(&self.pharos).broadcast(evt)
+    }
+
+    /// Whether the pharos is closed. See [Pharos::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        lock(&self.pharos).is_closed()
+    }
+}
+
+impl<Event> Sink<Event> for Producer<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut *lock(&self.pharos)).poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, evt: Event) -> Result<(), Self::Error> {
+        Pin::new(&mut *lock(&self.pharos)).start_send(evt)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut *lock(&self.pharos)).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut *lock(&self.pharos)).poll_close(cx)
+    }
+}
+
+impl<Event> fmt::Debug for Producer<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::Producer<{}>", type_name::<Event>())
+    }
+}
+
+/// The half of a [Pharos] that manages the observers, created with [Pharos::split]. It can add,
+/// remove and count observers, but not send events, that's up to the [Producer].
+//
+pub struct Registrar<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    pharos: Arc<Mutex<Pharos<Event>>>,
+}
+
+impl<Event> Registrar<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    /// Remove the observer with `id`. See [Pharos::remove_observer].
+    //
+    pub fn remove(&mut self, id: ObserverId) -> Result<(), Error> {
+        lock(&self.pharos).remove_observer(id)
+    }
+
+    /// The number of observers still listening. See [Pharos::num_observers].
+    //
+    pub fn num_observers(&mut self) -> usize {
+        lock(&self.pharos).num_observers()
+    }
+
+    /// The number of observers, without removing the ones that went away. See [Pharos::len].
+    //
+    pub fn len(&self) -> usize {
+        lock(&self.pharos).len()
+    }
+
+    /// Whether there are no observers. See [Pharos::is_empty].
+    //
+    pub fn is_empty(&self) -> bool {
+        lock(&self.pharos).is_empty()
+    }
+
+    /// Whether the pharos is closed. See [Pharos::is_closed].
+    //
+    pub fn is_closed(&self) -> bool {
+        lock(&self.pharos).is_closed()
+    }
+}
+
+impl<Event> Observable<Event> for Registrar<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    type Error = Error;
+
+    fn observe(&mut self, options: ObserveConfig<Event>) -> Result<Events<Event>, Self::Error> {
+        lock(&self.pharos).observe(options)
+    }
+}
+
+impl<Event> fmt::Debug for Registrar<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::Registrar<{}>", type_name::<Event>())
+    }
+}
+
+pub(crate) fn split<Event>(pharos: Pharos<Event>) -> (Producer<Event>, Registrar<Event>)
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    let pharos = Arc::new(Mutex::new(pharos));
+
+    (
+        Producer {
+            pharos: pharos.clone(),
+        },
+        Registrar { pharos },
+    )
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Observers added through the registrar get the events of the producer, until they are removed.
+    //
+    #[test]
+    //
+    fn split() {
+        let (mut producer, mut registrar) = Pharos::default().split();
+
+        let events = registrar
+            .observe(ObserveConfig::default())
+            .expect("observe");
+        let removed = registrar
+            .observe(ObserveConfig::default())
+            .expect("observe");
+
+        assert_eq!(2, registrar.len());
+
+        block_on(async {
+            producer.send(1).await.expect("send");
+
+            registrar.remove(removed.id()).expect("remove");
+            assert_eq!(1, registrar.len());
+
+            assert_eq!(
+                ErrorKind::UnknownObserver,
+                registrar.remove(removed.id()).unwrap_err().kind()
+            );
+
+            producer.send(2).await.expect("send");
+            producer.close().await.expect("close");
+
+            assert!(registrar.is_closed());
+            assert_eq!(vec![1, 2], events.collect::<Vec<_>>().await);
+            assert_eq!(vec![1], removed.collect::<Vec<_>>().await);
+        });
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{import::*, Error, Events, Observable, ObserveConfig, Pharos};
+
+/// A [Pharos] that is subscribed to another pharos and re-broadcasts the events it receives to its
+/// own observers. Created with [Pharos::subpharos]. Filtering at each level allows building trees
+/// of observables.
+///
+/// Events only flow while [SubPharos::run] is being polled. When the parent pharos closes or is
+/// dropped, `run` closes this pharos as well, so its observers see the end of their streams.
+//
+pub struct SubPharos<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    upstream: Events<Event>,
+    pharos: Pharos<Event>,
+}
+
+impl<Event> SubPharos<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    pub(crate) fn new(upstream: Events<Event>) -> Self {
+        Self {
+            upstream,
+            pharos: Pharos::default(),
+        }
+    }
+
+    /// Forward events from the parent to our observers until the parent closes, then close.
+    //
+    pub async fn run(&mut self) -> Result<(), Error> {
+        while let Some(evt) = self.upstream.next().await {
+            self.pharos.send(evt).await?;
+        }
+
+        self.pharos.close().await
+    }
+
+    /// Stop receiving events from the parent. Events that were already received can still be forwarded
+    /// with [SubPharos::run].
+    //
+    pub fn disconnect(&mut self) {
+        self.upstream.close();
+    }
+}
+
+impl<Event> Observable<Event> for SubPharos<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    type Error = Error;
+
+    fn observe(&mut self, options: ObserveConfig<Event>) -> Result<Events<Event>, Self::Error> {
+        self.pharos.observe(options)
+    }
+}
+
+impl<Event> fmt::Debug for SubPharos<Event>
+where
+    Event: 'static + Clone + Sync + Send,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::SubPharos<{}>", type_name::<Event>())
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use crate::{import::*, *};
+
+    // Only events that pass the filter reach observers of the child, and closing the parent
+    // closes the child.
+    //
+    #[test]
+    //
+    fn parent_child() {
+        let mut parent = Pharos::default();
+        let mut child = parent
+            .subpharos(Filter::Pointer(|n: &usize| *n > 2))
+            .expect("subpharos");
+
+        let all = parent.observe(ObserveConfig::default()).expect("observe");
+        let big = child.observe(ObserveConfig::default()).expect("observe");
+
+        block_on(async {
+            for n in 1..=4 {
+                parent.send(n).await.expect("send");
+            }
+
+            parent.close().await.expect("close");
+
+            child.run().await.expect("run");
+
+            assert_eq!(vec![1, 2, 3, 4], all.collect::<Vec<usize>>().await);
+            assert_eq!(vec![3, 4], big.collect::<Vec<usize>>().await);
+        });
+
+        let res = child.observe(ObserveConfig::default());
+
+        assert_eq!(ErrorKind::Closed, res.unwrap_err().kind());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    import::*,
+    queue::{queue, QueueReceiver, QueueSender},
+    wake_batch::WakeBatch,
+};
+
+/// Create a bounded channel that holds `cap` items, plus `reserved` slots that only high priority
+/// items may use. A flood of low priority items fills up at most `cap` slots, so there is always room
+/// for `reserved` high priority ones. Items are received in the order they were sent, whatever their
+/// priority. Used for [ObserveConfig::reserve](crate::ObserveConfig::reserve).
+//
+pub(crate) fn tiered<T>(cap: usize, reserved: usize) -> (TieredSender<T>, TieredReceiver<T>) {
+    let (tx, rx) = queue(cap + reserved);
+
+    (TieredSender { tx, cap, reserved }, rx)
+}
+
+pub(crate) type TieredReceiver<T> = QueueReceiver<T>;
+
+pub(crate) struct TieredSender<T> {
+    tx: QueueSender<T>,
+    cap: usize,
+    reserved: usize,
+}
+
+impl<T> TieredSender<T> {
+    /// Queue an item. Hands the item back if there is no room for an item of its priority or
+    /// the receiver is closed.
+    //
+    pub(crate) fn send(&self, item: T, high: bool) -> Result<(), T> {
+        self.tx
+            .push(item, None, |items, item| self.admit(items, item, high))
+    }
+
+    /// Like [TieredSender::send], but leaves waking the receiver to the batch.
+    //
+    pub(crate) fn send_deferred(
+        &self,
+        item: T,
+        high: bool,
+        batch: &mut WakeBatch,
+    ) -> Result<(), T> {
+        self.tx.push(item, Some(batch), |items, item| {
+            self.admit(items, item, high)
+        })
+    }
+
+    // Only take the item if there is room for an item of its priority.
+    //
+    fn admit(&self, items: &mut VecDeque<T>, item: T, high: bool) -> Result<(), T> {
+        if !self.has_room(items, high) {
+            return Err(item);
+        }
+
+        items.push_back(item);
+
+        Ok(())
+    }
+
+    // Whether an item of the given priority fits.
+    //
+    fn has_room(&self, items: &VecDeque<T>, high: bool) -> bool {
+        let limit = if high {
+            self.cap + self.reserved
+        } else {
+            self.cap
+        };
+
+        items.len() < limit
+    }
+
+    /// Ready once there is room for a low priority item, so any item fits.
+    //
+    pub(crate) fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.tx.poll_room(cx, |items| self.has_room(items, false))
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// Let the receiver know no more items will come.
+    //
+    pub(crate) fn close(&self) {
+        self.tx.close();
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use super::*;
+
+    // Low priority items only get the shared slots, high priority ones also the reserved slots.
+    //
+    #[test]
+    //
+    fn reserved() {
+        let (tx, rx) = tiered(2, 1);
+
+        assert_eq!(tx.send(1, false), Ok(()));
+        assert_eq!(tx.send(2, false), Ok(()));
+        assert_eq!(tx.send(3, false), Err(3));
+        assert_eq!(tx.send(4, true), Ok(()));
+        assert_eq!(tx.send(5, true), Err(5));
+
+        drop(tx);
+
+        assert_eq!(vec![1, 2, 4], block_on(rx.collect::<Vec<usize>>()));
+    }
+
+    // The sender notices when the receiver goes away.
+    //
+    #[test]
+    //
+    fn receiver_dropped() {
+        let (tx, rx) = tiered::<usize>(2, 1);
+
+        assert!(!tx.is_closed());
+
+        drop(rx);
+
+        assert!(tx.is_closed());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::import::*;
+
+/// A source of time, so features that need timers don't tie pharos to a specific runtime.
+/// Implement this with the timer of your executor, eg. `tokio::time::sleep`, and pass it to
+/// [Pharos::set_timer](crate::Pharos::set_timer).
+///
+/// With the `futures-timer` feature, [FuturesTimer] is available and is used by default.
+///
+/// ```
+/// use pharos::*;
+/// use std::{ future::Future, pin::Pin, time::{ Duration, Instant } };
+///
+/// struct MyTimer;
+///
+/// impl Timer for MyTimer
+/// {
+///    fn now( &self ) -> Instant { Instant::now() }
+///
+///    fn sleep( &self, _dur: Duration ) -> Pin<Box< dyn Future<Output=()> + Send >>
+///    {
+///       // Call the sleep function of your runtime here.
+///       //
+///       Box::pin( async {} )
+///    }
+/// }
+/// ```
+//
+pub trait Timer: Send + Sync {
+    /// The current time.
+    //
+    fn now(&self) -> Instant;
+
+    /// A future that resolves once `dur` has passed.
+    //
+    fn sleep(&self, dur: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+/// A [Timer] backed by the [futures-timer](https://docs.rs/futures-timer) crate, which runs its own
+/// timer thread and works with any executor. Requires the `futures-timer` feature.
+//
+#[cfg(feature = "futures-timer")]
+//
+#[derive(Debug, Copy, Clone, Default)]
+//
+pub struct FuturesTimer;
+
+#[cfg(feature = "futures-timer")]
+//
+impl Timer for FuturesTimer {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, dur: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(futures_timer::Delay::new(dur))
+    }
+}
+
+// The timer a new pharos starts out with.
+//
+#[cfg(feature = "futures-timer")]
+//
+pub(crate) fn default_timer() -> Option<Arc<dyn Timer>> {
+    Some(Arc::new(FuturesTimer))
+}
+
+#[cfg(not(feature = "futures-timer"))]
+//
+pub(crate) fn default_timer() -> Option<Arc<dyn Timer>> {
+    None
+}
+
+/// A timer that only moves when told to, so tests with timeouts are deterministic.
+//
+#[cfg(test)]
+//
+#[derive(Clone)]
+//
+pub(crate) struct MockTimer {
+    start: Instant,
+    inner: Arc<Mutex<MockInner>>,
+}
+
+#[cfg(test)]
+//
+struct MockInner {
+    elapsed: Duration,
+    sleepers: Vec<Waker>,
+}
+
+#[cfg(test)]
+//
+impl MockTimer {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            inner: Arc::new(Mutex::new(MockInner {
+                elapsed: Duration::from_secs(0),
+                sleepers: Vec::new(),
+            })),
+        }
+    }
+
+    /// Move time forward and wake all sleepers so they can check whether they are due.
+    //
+    pub(crate) fn advance(&self, dur: Duration) {
+        let sleepers = {
+            let mut inner = self.inner.lock().expect("lock");
+            inner.elapsed += dur;
+            mem::take(&mut inner.sleepers)
+        };
+
+        sleepers.into_iter().for_each(|w| w.wake());
+    }
+}
+
+#[cfg(test)]
+//
+impl Timer for MockTimer {
+    fn now(&self) -> Instant {
+        self.start + self.inner.lock().expect("lock").elapsed
+    }
+
+    fn sleep(&self, dur: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let deadline = self.now() + dur;
+        let this = self.clone();
+
+        Box::pin(poll_fn(move |cx| {
+            if this.now() >= deadline {
+                return Poll::Ready(());
+            }
+
+            this.inner
+                .lock()
+                .expect("lock")
+                .sleepers
+                .push(cx.waker().clone());
+
+            Poll::Pending
+        }))
+    }
+}
+
+#[cfg(test)]
+//
+impl fmt::Debug for MockTimer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pharos::timer::MockTimer")
+    }
+}
@@ -0,0 +1,51 @@
+use crate::import::*;
+
+/// Wakers collected while an event goes out, to wake every task once when the batch is dropped.
+/// When one task polls several observers, eg. a merged stream, it would otherwise be woken for
+/// each of them.
+///
+/// Only the channels pharos implements itself defer their wake ups to a batch. The channels from
+/// `futures_channel` wake their receiver on their own.
+//
+#[derive(Default)]
+//
+pub(crate) struct WakeBatch {
+    wakers: Vec<Waker>,
+}
+
+impl WakeBatch {
+    /// Remember a waker, unless one that wakes the same task is already there.
+    //
+    pub(crate) fn defer(&mut self, waker: Waker) {
+        if !self.wakers.iter().any(|w| w.will_wake(&waker)) {
+            self.wakers.push(waker);
+        }
+    }
+}
+
+// Waking on drop makes sure no task is forgotten when sending stops early on an error.
+//
+impl Drop for WakeBatch {
+    fn drop(&mut self) {
+        self.wakers.drain(..).for_each(Waker::wake);
+    }
+}
+
+#[cfg(test)]
+//
+mod tests {
+    use super::*;
+
+    // A clone of a waker is recognized as the same task.
+    //
+    #[test]
+    //
+    fn dedup() {
+        let mut batch = WakeBatch::default();
+
+        batch.defer(noop_waker_ref().clone());
+        batch.defer(noop_waker_ref().clone());
+
+        assert_eq!(1, batch.wakers.len());
+    }
+}
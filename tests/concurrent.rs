@@ -0,0 +1,88 @@
+// Tested:
+//
+// - ✔ producers on several threads sharing a pharos deliver every event exactly once and intact
+//
+mod common;
+
+use common::import::*;
+use futures::lock::Mutex;
+
+// An event that can tell if it got corrupted.
+//
+#[derive(Clone, Debug, PartialEq)]
+//
+struct Numbered {
+    n: u32,
+    text: String,
+}
+
+impl Numbered {
+    fn new(n: u32) -> Self {
+        Self {
+            n,
+            text: n.to_string(),
+        }
+    }
+}
+
+// Two producers send distinct ranges through a shared pharos while one observer reads.
+//
+#[test]
+//
+fn shared_producers() {
+    const PER_PRODUCER: u32 = 1000;
+
+    let pharos = Arc::new(Mutex::new(Pharos::default()));
+
+    let events = block_on(pharos.lock())
+        .observe(Channel::Bounded(4).into())
+        .expect("observe");
+
+    let producers: Vec<_> = (0..2)
+        .map(|p| {
+            let pharos = pharos.clone();
+
+            thread::spawn(move || {
+                block_on(async move {
+                    for n in p * PER_PRODUCER..(p + 1) * PER_PRODUCER {
+                        pharos
+                            .lock()
+                            .await
+                            .send(Numbered::new(n))
+                            .await
+                            .expect("send");
+                    }
+                });
+            })
+        })
+        .collect();
+
+    let consumer =
+        thread::spawn(move || block_on(events.take(2 * PER_PRODUCER as usize).collect::<Vec<_>>()));
+
+    for producer in producers {
+        producer.join().expect("join producer");
+    }
+
+    let received = consumer.join().expect("join consumer");
+
+    assert_eq!(2 * PER_PRODUCER as usize, received.len());
+
+    for evt in &received {
+        assert_eq!(evt.n.to_string(), evt.text);
+    }
+
+    // The events of each producer arrive in the order they were sent.
+    //
+    for p in 0..2 {
+        let range = p * PER_PRODUCER..(p + 1) * PER_PRODUCER;
+
+        let ours: Vec<u32> = received
+            .iter()
+            .map(|evt| evt.n)
+            .filter(|n| range.contains(n))
+            .collect();
+
+        assert_eq!(range.collect::<Vec<_>>(), ours);
+    }
+}
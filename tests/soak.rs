@@ -0,0 +1,114 @@
+// Tested:
+//
+// - ✔ observers coming and going on many threads while a producer broadcasts keep the slot bookkeeping
+//     consistent and the storage bounded by the number of live observers
+//
+#![cfg(feature = "testing")]
+
+mod common;
+
+use common::import::*;
+use futures::FutureExt;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+const SUBSCRIBERS: usize = 8;
+const ITERATIONS: usize = 500;
+
+// Every subscriber holds at most one observer at a time, so after cleaning up closed observers, the
+// storage never needs more slots than there are subscribers.
+//
+#[test]
+//
+fn observe_send_drop() {
+    let pharos = Arc::new(Mutex::new(Pharos::<usize>::default()));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let producer = {
+        let pharos = pharos.clone();
+        let stop = stop.clone();
+
+        thread::spawn(move || {
+            let mut n = 0;
+
+            while !stop.load(Ordering::Acquire) {
+                {
+                    let mut ph = pharos.lock().expect("lock");
+
+                    // Full bounded observers make this fail, they get removed.
+                    //
+                    let _ = ph.broadcast(n);
+                    ph.assert_invariants();
+                }
+
+                n += 1;
+                thread::yield_now();
+            }
+        })
+    };
+
+    let subscribers: Vec<_> = (0..SUBSCRIBERS)
+        .map(|s| {
+            let pharos = pharos.clone();
+
+            thread::spawn(move || {
+                for i in 0..ITERATIONS {
+                    let channel = match (s + i) % 3 {
+                        0 => Channel::Unbounded,
+                        1 => Channel::Bounded(4),
+                        _ => Channel::RingBuffer(4),
+                    };
+
+                    let mut events = {
+                        let mut ph = pharos.lock().expect("lock");
+
+                        // Counting removes the observers that went away, which frees their slots for reuse.
+                        // The others each hold one at most, and we dropped ours.
+                        //
+                        let live = ph.num_observers();
+                        assert!(live < SUBSCRIBERS);
+
+                        let events = ph.observe(channel.into()).expect("observe");
+
+                        assert!(ph.storage_len() <= SUBSCRIBERS);
+                        ph.assert_invariants();
+
+                        events
+                    };
+
+                    // Events come out in the order they were sent.
+                    //
+                    let mut last = None;
+
+                    for _ in 0..i % 5 {
+                        thread::yield_now();
+
+                        if let Some(Some(evt)) = events.next().now_or_never() {
+                            assert!(last < Some(evt));
+                            last = Some(evt);
+                        }
+                    }
+
+                    if i % 2 == 0 {
+                        events.close();
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for subscriber in subscribers {
+        subscriber.join().expect("join subscriber");
+    }
+
+    stop.store(true, Ordering::Release);
+    producer.join().expect("join producer");
+
+    let mut ph = pharos.lock().expect("lock");
+
+    assert_eq!(0, ph.num_observers());
+    assert!(ph.storage_len() <= SUBSCRIBERS);
+    ph.assert_invariants();
+}